@@ -0,0 +1,279 @@
+//! Arithmetic for solver accumulations.
+//!
+//! Built with the `checked` feature, every operation is checked and overflow comes
+//! back as an [`Overflow`] naming the day, the operation and the input line. Without
+//! it, these are the plain operators, so solvers behave exactly as they always have.
+//! Either way, text that isn't a number comes back from [`Location::parse`] as an
+//! [`Invalid`] pointing at the same place.
+//!
+//! Only days 1, 2, 6, 7, 10, 16 and 17 route their arithmetic through here, and only
+//! they have a `checked` feature to pass on. The other days still use the plain
+//! operators, including day 14's pair counts, which wait on its unfinished
+//! `polymerize_v3`.
+
+use std::error;
+use std::fmt;
+use std::num::ParseIntError;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Parse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub day: u8,
+    pub operation: Operation,
+    /// The 1-based input line being processed, if the value came from the input
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}: {:?} overflowed", self.day, self.operation)?;
+        if let Some(line) = self.line {
+            write!(f, " on input line {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Overflow {}
+
+/// Input text that isn't a number at all, as opposed to one too big for its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invalid {
+    pub day: u8,
+    pub text: String,
+    /// The 1-based input line being processed, if the value came from the input
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}: {:?} isn't a number", self.day, self.text)?;
+        if let Some(line) = self.line {
+            write!(f, " on input line {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Invalid {}
+
+/// Anything [`Location::parse`] can fail with. Arithmetic only ever overflows, so
+/// `?` on an [`Overflow`] converts into this.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Overflow(Overflow),
+    Invalid(Invalid),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Overflow(overflow) => overflow.fmt(f),
+            Error::Invalid(invalid) => invalid.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Overflow> for Error {
+    fn from(overflow: Overflow) -> Self {
+        Error::Overflow(overflow)
+    }
+}
+
+pub trait Integer:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Sized
+{
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Where an operation happens, so an overflow can point back at it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub day: u8,
+    pub line: Option<usize>,
+}
+
+impl Location {
+    pub const fn new(day: u8) -> Self {
+        Location { day, line: None }
+    }
+
+    pub const fn line(day: u8, line: usize) -> Self {
+        Location {
+            day,
+            line: Some(line),
+        }
+    }
+
+    #[cfg(feature = "checked")]
+    fn overflow(self, operation: Operation) -> Overflow {
+        Overflow {
+            day: self.day,
+            operation,
+            line: self.line,
+        }
+    }
+
+    #[cfg(feature = "checked")]
+    pub fn add<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        a.checked_add(b)
+            .ok_or_else(|| self.overflow(Operation::Add))
+    }
+
+    #[cfg(not(feature = "checked"))]
+    pub fn add<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        Ok(a + b)
+    }
+
+    #[cfg(feature = "checked")]
+    pub fn sub<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        a.checked_sub(b)
+            .ok_or_else(|| self.overflow(Operation::Sub))
+    }
+
+    #[cfg(not(feature = "checked"))]
+    pub fn sub<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        Ok(a - b)
+    }
+
+    #[cfg(feature = "checked")]
+    pub fn mul<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        a.checked_mul(b)
+            .ok_or_else(|| self.overflow(Operation::Mul))
+    }
+
+    #[cfg(not(feature = "checked"))]
+    pub fn mul<T: Integer>(self, a: T, b: T) -> Result<T, Overflow> {
+        Ok(a * b)
+    }
+
+    fn invalid(self, src: &str) -> Error {
+        Error::Invalid(Invalid {
+            day: self.day,
+            text: src.to_string(),
+            line: self.line,
+        })
+    }
+
+    /// Parses `src` in `radix`. Digits too big for `T` are an overflow, anything else
+    /// that isn't a number is [`Invalid`].
+    #[cfg(feature = "checked")]
+    pub fn parse<T: Integer>(self, src: &str, radix: u32) -> Result<T, Error> {
+        T::from_str_radix(src, radix).map_err(|error| match error.kind() {
+            std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow => {
+                self.overflow(Operation::Parse).into()
+            }
+            _ => self.invalid(src),
+        })
+    }
+
+    /// Parses `src` in `radix`. Without overflow checks, digits too big for `T` are
+    /// just another [`Invalid`] number.
+    #[cfg(not(feature = "checked"))]
+    pub fn parse<T: Integer>(self, src: &str, radix: u32) -> Result<T, Error> {
+        T::from_str_radix(src, radix).map_err(|_| self.invalid(src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_does_arithmetic() {
+        let at = Location::line(1, 3);
+        assert_eq!(at.add(2u16, 3), Ok(5));
+        assert_eq!(at.sub(5i32, 8), Ok(-3));
+        assert_eq!(at.mul(4usize, 5), Ok(20));
+        assert_eq!(at.parse::<usize>("10110", 2), Ok(22));
+    }
+
+    #[test]
+    fn it_reports_invalid_numbers() {
+        let invalid = Error::Invalid(Invalid {
+            day: 1,
+            text: "2oo".to_string(),
+            line: Some(2),
+        });
+        assert_eq!(
+            Location::line(1, 2).parse::<u16>("2oo", 10),
+            Err(invalid.clone())
+        );
+        assert_eq!(
+            invalid.to_string(),
+            "day 1: \"2oo\" isn't a number on input line 2"
+        );
+        assert!(matches!(
+            Location::new(16).parse::<usize>("", 2),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflow() {
+        let at = Location::line(2, 4);
+        let overflow = Overflow {
+            day: 2,
+            operation: Operation::Sub,
+            line: Some(4),
+        };
+        assert_eq!(at.sub(3u16, 5), Err(overflow.clone()));
+        assert_eq!(
+            overflow.to_string(),
+            "day 2: Sub overflowed on input line 4"
+        );
+
+        assert_eq!(
+            Location::new(16).parse::<usize>(&"1".repeat(65), 2),
+            Err(Error::Overflow(Overflow {
+                day: 16,
+                operation: Operation::Parse,
+                line: None,
+            }))
+        );
+        assert_eq!(
+            Location::new(17).add(i32::MAX, 1).unwrap_err().operation,
+            Operation::Add
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use std::env;
+use std::fs;
+
+/// Reads the puzzle input from the path in `AOC_INPUT`, as set by the runner, or
+/// from `input` in the working directory when run on its own.
+pub fn read_input() -> String {
+    let path = env::var("AOC_INPUT").unwrap_or_else(|_| "input".to_string());
+    fs::read_to_string(path).expect("couldn't read the input")
+}
+
+pub fn split_input_by_blankline(input: &str) -> Vec<String> {
+    input
+        .split("\n\n")
+        .map(|string| string.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_the_input_by_blankline() {
+        let input = "7,4,9\n\n22 13\n8 2\n\n3 15\n9 18";
+        let output = split_input_by_blankline(input);
+        assert_eq!(output, vec!["7,4,9", "22 13\n8 2", "3 15\n9 18"]);
+    }
+}
@@ -0,0 +1,4 @@
+//! Helpers shared by every year's solvers.
+
+pub mod checked;
+pub mod input;
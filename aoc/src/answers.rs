@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Known-good answers, keyed by (year, day, part).
+pub type Answers = HashMap<(u16, u8, u8), String>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    /// The line stops before this field
+    Missing(&'static str),
+    /// This field isn't a number, or isn't a valid one
+    Bad(&'static str, String),
+    /// Anything after the answer
+    Extra(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Missing(field) => write!(f, "missing {}", field),
+            ErrorKind::Bad(field, value) => write!(f, "bad {} {:?}", field, value),
+            ErrorKind::Extra(extra) => write!(f, "unexpected {:?} after the answer", extra),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The 1-based line in the answers file
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// The answers in `path`, or none if there's no such file.
+pub fn load_answers(path: &Path) -> Result<Answers, ParseError> {
+    match fs::read_to_string(path) {
+        Ok(input) => parse_answers(&input),
+        Err(_) => Ok(Answers::new()),
+    }
+}
+
+/// Parses lines of `year day part answer`, skipping blank lines and `#` comments.
+fn parse_answers(input: &str) -> Result<Answers, ParseError> {
+    let mut output = Answers::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |kind| ParseError {
+            line: index + 1,
+            kind,
+        };
+
+        let mut fields = line.split_whitespace();
+        let mut next = |name| fields.next().ok_or_else(|| error(ErrorKind::Missing(name)));
+        let (year, day, part, answer) =
+            (next("year")?, next("day")?, next("part")?, next("answer")?);
+        let bad = |name, value: &str| error(ErrorKind::Bad(name, value.to_string()));
+        let year = year.parse().map_err(|_| bad("year", year))?;
+        let day = day.parse().map_err(|_| bad("day", day))?;
+        let part = match part {
+            "1" => 1,
+            "2" => 2,
+            _ => return Err(bad("part", part)),
+        };
+
+        let extra: Vec<&str> = fields.collect();
+        if !extra.is_empty() {
+            return Err(error(ErrorKind::Extra(extra.join(" "))));
+        }
+        output.insert((year, day, part), answer.to_string());
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_answers() {
+        let input = r#"# year day part answer
+2021 1 1 7
+2021 1 2 5
+
+2021 4 2 1924
+2022 1 1 24000"#;
+        let answers = parse_answers(input).unwrap();
+        assert_eq!(answers.len(), 4);
+        assert_eq!(answers.get(&(2021, 1, 2)), Some(&"5".to_string()));
+        assert_eq!(answers.get(&(2021, 4, 2)), Some(&"1924".to_string()));
+        assert_eq!(answers.get(&(2021, 4, 1)), None);
+        assert_eq!(answers.get(&(2022, 1, 1)), Some(&"24000".to_string()));
+    }
+
+    #[test]
+    fn it_names_the_bad_line() {
+        let error = |input| parse_answers(input).unwrap_err().to_string();
+        assert_eq!(error("2021 1 1 7\n2021 1 2"), "line 2: missing answer");
+        assert_eq!(
+            error("# year day part answer\n2021 x 1 7"),
+            "line 2: bad day \"x\""
+        );
+        assert_eq!(error("2021 1 3 7"), "line 1: bad part \"3\"");
+        assert_eq!(
+            error("\n\n2021 1 1 7 increases"),
+            "line 3: unexpected \"increases\" after the answer"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Everything an answer depends on. If none of it has changed, neither has the answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// The file handed to the solver in `AOC_INPUT`
+    pub input: u64,
+    pub parameters: u64,
+    pub build: u64,
+}
+
+impl Key {
+    fn file_name(&self) -> String {
+        format!(
+            "{}-{}-{}-{:016x}-{:016x}-{:016x}",
+            self.year, self.day, self.part, self.input, self.parameters, self.build
+        )
+    }
+}
+
+/// Answers from earlier runs, one file per key.
+pub struct Cache {
+    dir: PathBuf,
+    /// When false, answers are always recomputed, but still written back
+    pub read: bool,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, read: bool) -> Self {
+        Cache { dir, read }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<String> {
+        if !self.read {
+            return None;
+        }
+        fs::read_to_string(self.dir.join(key.file_name())).ok()
+    }
+
+    pub fn put(&self, key: &Key, value: &str) {
+        fs::create_dir_all(&self.dir).expect("couldn't create the cache directory");
+        fs::write(self.dir.join(key.file_name()), value).expect("couldn't write to the cache");
+    }
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher gives the same value on
+/// every Rust release, so cache entries outlive a toolchain update.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn hash_file(path: &Path) -> u64 {
+    hash_bytes(&fs::read(path).expect("couldn't read the file to hash"))
+}
+
+pub fn hash_parameters(parameters: &[String]) -> u64 {
+    hash_bytes(parameters.join("\0").as_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    const KEY: Key = Key {
+        year: 2021,
+        day: 12,
+        part: 2,
+        input: 0xabc,
+        parameters: 0,
+        build: 0xdef,
+    };
+
+    #[test]
+    fn it_names_files_after_the_key() {
+        assert_eq!(
+            KEY.file_name(),
+            "2021-12-2-0000000000000abc-0000000000000000-0000000000000def"
+        );
+    }
+
+    #[test]
+    fn it_caches_answers() {
+        let dir = env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let cache = Cache::new(dir.clone(), true);
+        assert_eq!(cache.get(&KEY), None);
+
+        cache.put(&KEY, "118803");
+        assert_eq!(cache.get(&KEY), Some("118803".to_string()));
+        assert_eq!(
+            cache.get(&Key {
+                build: 0x123,
+                ..KEY
+            }),
+            None
+        );
+        assert_eq!(cache.get(&Key { part: 1, ..KEY }), None);
+
+        let bypassed = Cache::new(dir.clone(), false);
+        assert_eq!(bypassed.get(&KEY), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn it_hashes_bytes_stably() {
+        // Reference values for 64-bit FNV-1a
+        assert_eq!(hash_bytes(b""), 0xcbf29ce484222325);
+        assert_eq!(hash_bytes(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash_bytes(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn it_hashes_parameters_by_value() {
+        let a = vec!["--render".to_string(), "out.svg".to_string()];
+        let b = vec!["--render out.svg".to_string()];
+        assert_eq!(hash_parameters(&a), hash_parameters(&a.clone()));
+        assert_ne!(hash_parameters(&a), hash_parameters(&b));
+        assert_ne!(hash_parameters(&a), hash_parameters(&[]));
+    }
+}
@@ -0,0 +1,195 @@
+mod answers;
+mod cache;
+mod registry;
+mod report;
+mod runner;
+mod status;
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use answers::load_answers;
+use cache::Cache;
+use report::{get_row, print_answers, Row};
+use runner::run_solver;
+
+const USAGE: &str = r#"usage:
+    aoc run (--all | [--year YEAR] --day DAY) [--no-cache] [--render PATH] [-- SOLVER_ARGS...]
+    aoc status [--year YEAR] [--day DAY] [--no-cache] [--readme]"#;
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    command: String,
+    year: Option<u16>,
+    day: Option<u8>,
+    all: bool,
+    no_cache: bool,
+    readme: bool,
+    /// Where a single day should draw its chart
+    render: Option<PathBuf>,
+    /// Passed through to the solvers
+    parameters: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Option<Args> {
+    let mut iter = args.iter();
+    let mut output = Args {
+        command: iter.next()?.to_string(),
+        ..Args::default()
+    };
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--year" => output.year = Some(iter.next()?.parse().ok()?),
+            "--day" => output.day = Some(iter.next()?.parse().ok()?),
+            "--all" => output.all = true,
+            "--no-cache" => output.no_cache = true,
+            "--readme" => output.readme = true,
+            "--render" => output.render = Some(PathBuf::from(iter.next()?)),
+            "--" => {
+                output.parameters = iter.by_ref().cloned().collect();
+            }
+            _ => return None,
+        }
+    }
+
+    Some(output)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args = parse_args(&args).unwrap_or_else(|| usage());
+    let root = repository_root();
+
+    match args.command.as_str() {
+        "run" => {
+            if !args.all && args.day.is_none() {
+                usage();
+            }
+            // A bare day means the latest year that has it
+            let year = args.year.or_else(|| {
+                args.day.and_then(|day| {
+                    registry::select(None, Some(day))
+                        .iter()
+                        .map(|solver| solver.year)
+                        .max()
+                })
+            });
+            if let Some(path) = &args.render {
+                if args.all {
+                    usage();
+                }
+                if !registry::select(year, args.day)
+                    .iter()
+                    .all(|solver| solver.renders)
+                {
+                    let days: Vec<String> = registry::SOLVERS
+                        .iter()
+                        .filter(|solver| solver.renders)
+                        .map(|solver| format!("{} day {}", solver.year, solver.day))
+                        .collect();
+                    eprintln!("--render only works for {}", days.join(", "));
+                    process::exit(2);
+                }
+                // Solvers run from their own crate, so relative paths would land there
+                let path = std::path::absolute(path).expect("couldn't resolve the render path");
+                args.parameters.push("--render".to_string());
+                args.parameters.push(path.display().to_string());
+            }
+            print_answers(&get_rows(&root, year, &args));
+        }
+        "status" => {
+            // The README table always covers every solver
+            if args.render.is_some()
+                || args.readme
+                    && (args.year.is_some() || args.day.is_some() || !args.parameters.is_empty())
+            {
+                usage();
+            }
+            let rows = get_rows(&root, args.year, &args);
+            status::status(&root, &rows, args.readme);
+        }
+        _ => usage(),
+    }
+}
+
+fn get_rows(root: &Path, year: Option<u16>, args: &Args) -> Vec<Row> {
+    let answers = load_answers(&root.join("answers")).unwrap_or_else(|error| {
+        eprintln!("answers: {}", error);
+        process::exit(1);
+    });
+    // A cached answer would skip the run, and with it the chart or the README's timing
+    let cache = Cache::new(
+        root.join(".cache"),
+        !args.no_cache && args.render.is_none() && !args.readme,
+    );
+    registry::select(year, args.day)
+        .into_iter()
+        .map(|solver| {
+            let run = run_solver(solver, root, &args.parameters, &cache);
+            get_row(solver, &run, &answers)
+        })
+        .collect()
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn repository_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("couldn't find the repository root")
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        input
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_parses_args() {
+        let output = parse_args(&args("run --year 2021 --day 5")).unwrap();
+        assert_eq!(
+            output,
+            Args {
+                command: "run".to_string(),
+                year: Some(2021),
+                day: Some(5),
+                ..Args::default()
+            }
+        );
+
+        let output = parse_args(&args("status --readme")).unwrap();
+        assert!(output.readme);
+        assert!(!output.no_cache);
+        assert_eq!(output.year, None);
+
+        let output = parse_args(&args("run --all --no-cache -- --render out.svg")).unwrap();
+        assert!(output.all);
+        assert!(output.no_cache);
+        assert_eq!(output.parameters, vec!["--render", "out.svg"]);
+
+        let output = parse_args(&args("run --day 1 --render out.svg -- --highlight 5..9")).unwrap();
+        assert_eq!(output.render, Some(PathBuf::from("out.svg")));
+        assert_eq!(output.parameters, vec!["--highlight", "5..9"]);
+    }
+
+    #[test]
+    fn it_rejects_bad_args() {
+        assert_eq!(parse_args(&args("")), None);
+        assert_eq!(parse_args(&args("run --day five")), None);
+        assert_eq!(parse_args(&args("run --year")), None);
+        assert_eq!(parse_args(&args("run --verbose")), None);
+        assert_eq!(parse_args(&args("run --day 1 --render")), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+
+/// Where a solver reports one part's answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reported {
+    /// On a `part N:` line
+    Labelled,
+    /// The solver's nth `dbg!` value, counting from 0
+    Value(usize),
+}
+
+/// A day's solver crate, and where its answers show up in what it reports.
+pub struct Solver {
+    pub year: u16,
+    pub day: u8,
+    /// Where part one and part two are reported, if the solver reports them.
+    pub parts: [Option<Reported>; 2],
+    /// Whether the solver can draw a chart with `--render PATH`
+    pub renders: bool,
+}
+
+impl Solver {
+    fn crate_name(&self) -> String {
+        format!("aoc{}-{}", self.year, self.day)
+    }
+
+    pub fn crate_dir(&self, root: &Path) -> PathBuf {
+        root.join(self.crate_name())
+    }
+
+    pub fn binary(&self, root: &Path) -> PathBuf {
+        self.crate_dir(root)
+            .join("target")
+            .join("release")
+            .join(self.crate_name())
+    }
+
+    /// Inputs live in `inputs/<year>/<day>`, except for 2021, whose inputs are still
+    /// kept next to each day's crate.
+    pub fn input(&self, root: &Path) -> PathBuf {
+        let input = root
+            .join("inputs")
+            .join(self.year.to_string())
+            .join(self.day.to_string());
+        if input.exists() {
+            input
+        } else {
+            self.crate_dir(root).join("input")
+        }
+    }
+}
+
+/// Solvers for the given year and day, or all of them when neither is given.
+pub fn select(year: Option<u16>, day: Option<u8>) -> Vec<&'static Solver> {
+    SOLVERS
+        .iter()
+        .filter(|solver| year.is_none_or(|year| solver.year == year))
+        .filter(|solver| day.is_none_or(|day| solver.day == day))
+        .collect()
+}
+
+pub const SOLVERS: &[Solver] = &[
+    Solver {
+        year: 2021,
+        day: 1,
+        parts: [Some(Reported::Labelled), Some(Reported::Labelled)],
+        renders: true,
+    },
+    // Day 2 reports x, y and their product for each part
+    Solver {
+        year: 2021,
+        day: 2,
+        parts: [Some(Reported::Value(2)), Some(Reported::Value(5))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 3,
+        parts: [Some(Reported::Labelled), Some(Reported::Labelled)],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 4,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    // Day 5's solver was rewritten in place to handle diagonals for part two
+    Solver {
+        year: 2021,
+        day: 5,
+        parts: [None, Some(Reported::Value(0))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 6,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 7,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 8,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 9,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 10,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 11,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 12,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    // Day 13's part two answer is read off the printed paper
+    Solver {
+        year: 2021,
+        day: 13,
+        parts: [Some(Reported::Value(0)), None],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 14,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 15,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 16,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+    Solver {
+        year: 2021,
+        day: 17,
+        parts: [Some(Reported::Value(0)), Some(Reported::Value(1))],
+        renders: false,
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_selects_solvers() {
+        assert_eq!(select(None, None).len(), SOLVERS.len());
+        assert_eq!(select(Some(2021), None).len(), 17);
+        assert_eq!(select(Some(2020), None).len(), 0);
+
+        let selected = select(Some(2021), Some(5));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].day, 5);
+        assert_eq!(selected[0].parts, [None, Some(Reported::Value(0))]);
+        assert!(!selected[0].renders);
+        assert!(select(Some(2021), Some(1))[0].renders);
+    }
+
+    #[test]
+    fn it_finds_2021_inputs_next_to_the_crate() {
+        let root = Path::new("/repo");
+        let solver = select(Some(2021), Some(1))[0];
+        assert_eq!(solver.crate_dir(root), Path::new("/repo/aoc2021-1"));
+        assert_eq!(solver.input(root), Path::new("/repo/aoc2021-1/input"));
+        assert_eq!(
+            solver.binary(root),
+            Path::new("/repo/aoc2021-1/target/release/aoc2021-1")
+        );
+    }
+}
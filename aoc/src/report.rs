@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use crate::answers::Answers;
+use crate::registry::Solver;
+use crate::runner::{Outcome, Run};
+
+#[derive(Debug, PartialEq)]
+pub enum Star {
+    /// The solver's answer matches the stored answer
+    Verified,
+    /// The solver's answer doesn't match the stored answer
+    Wrong,
+    /// The solver gave an answer, but there's nothing stored to check it against
+    Unverified,
+    /// The solver gave no answer
+    Missing,
+}
+
+impl Star {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Star::Verified => "★",
+            Star::Wrong => "✗",
+            Star::Unverified => "?",
+            Star::Missing => "·",
+        }
+    }
+}
+
+/// How one solver did, part by part.
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub values: [Option<String>; 2],
+    pub stars: [Star; 2],
+    pub elapsed: Option<Duration>,
+    pub cached: bool,
+    pub note: &'static str,
+}
+
+pub fn get_row(solver: &Solver, run: &Run, answers: &Answers) -> Row {
+    let mut values = [None, None];
+    let mut stars = [Star::Missing, Star::Missing];
+    let note = match &run.outcome {
+        Outcome::Answered {
+            answers: reported, ..
+        } => {
+            for part in 0..2 {
+                let value = reported[part].as_ref();
+                let answer = answers.get(&(solver.year, solver.day, part as u8 + 1));
+                stars[part] = match (value, answer) {
+                    (None, _) => Star::Missing,
+                    (Some(_), None) => Star::Unverified,
+                    (Some(value), Some(answer)) if value == answer => Star::Verified,
+                    (Some(_), Some(_)) => Star::Wrong,
+                };
+                values[part] = value.cloned();
+            }
+            ""
+        }
+        Outcome::MissingInput => "missing input",
+        Outcome::BuildFailed => "build failed",
+        Outcome::Panicked => "panicked",
+    };
+
+    Row {
+        year: solver.year,
+        day: solver.day,
+        values,
+        stars,
+        elapsed: match run.outcome {
+            Outcome::Answered { .. } => Some(run.elapsed),
+            _ => None,
+        },
+        cached: matches!(run.outcome, Outcome::Answered { cached: true, .. }),
+        note,
+    }
+}
+
+/// How long the solver took, or `cached` if it didn't have to run.
+pub fn format_elapsed(row: &Row) -> String {
+    match row.elapsed {
+        Some(_) if row.cached => "cached".to_string(),
+        Some(elapsed) => format!("{:.1} ms", elapsed.as_secs_f64() * 1000.0),
+        None => "".to_string(),
+    }
+}
+
+/// Prints each part's answer alongside whether it matches the stored one.
+pub fn print_answers(rows: &[Row]) {
+    for row in rows {
+        if !row.note.is_empty() {
+            println!("{} day {}: {}", row.year, row.day, row.note);
+            continue;
+        }
+
+        println!("{} day {} ({})", row.year, row.day, format_elapsed(row));
+        for part in 0..2 {
+            let line = format!(
+                "  part {}: {} {}",
+                part + 1,
+                row.stars[part].symbol(),
+                row.values[part].as_deref().unwrap_or("")
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry::Reported;
+
+    const SOLVER: Solver = Solver {
+        year: 2021,
+        day: 2,
+        parts: [Some(Reported::Value(2)), Some(Reported::Value(5))],
+        renders: false,
+    };
+
+    #[test]
+    fn it_checks_reported_values_against_answers() {
+        let run = Run {
+            outcome: Outcome::Answered {
+                answers: [Some("150".to_string()), Some("901".to_string())],
+                cached: false,
+            },
+            elapsed: Duration::from_millis(3),
+        };
+        let mut answers = Answers::new();
+        answers.insert((2021, 2, 1), "150".to_string());
+        answers.insert((2021, 2, 2), "900".to_string());
+        answers.insert((2022, 2, 2), "901".to_string());
+
+        let row = get_row(&SOLVER, &run, &answers);
+        assert_eq!(row.stars, [Star::Verified, Star::Wrong]);
+        assert_eq!(
+            row.values,
+            [Some("150".to_string()), Some("901".to_string())]
+        );
+
+        assert_eq!(format_elapsed(&row), "3.0 ms");
+
+        let row = get_row(&SOLVER, &run, &Answers::new());
+        assert_eq!(row.stars, [Star::Unverified, Star::Unverified]);
+    }
+
+    #[test]
+    fn it_notes_cached_answers() {
+        let run = Run {
+            outcome: Outcome::Answered {
+                answers: [Some("150".to_string()), None],
+                cached: true,
+            },
+            elapsed: Duration::ZERO,
+        };
+        let row = get_row(&SOLVER, &run, &Answers::new());
+        assert_eq!(row.stars, [Star::Unverified, Star::Missing]);
+        assert!(row.cached);
+        assert_eq!(format_elapsed(&row), "cached");
+    }
+
+    #[test]
+    fn it_notes_solvers_that_did_not_run() {
+        let run = Run {
+            outcome: Outcome::BuildFailed,
+            elapsed: Duration::ZERO,
+        };
+        let row = get_row(&SOLVER, &run, &Answers::new());
+        assert_eq!(row.stars, [Star::Missing, Star::Missing]);
+        assert_eq!(row.values, [None, None]);
+        assert_eq!(row.note, "build failed");
+        assert_eq!(row.elapsed, None);
+    }
+}
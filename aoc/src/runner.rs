@@ -0,0 +1,286 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::cache::{hash_file, hash_parameters, Cache, Key};
+use crate::registry::{Reported, Solver};
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// Answers to part one and part two, and whether they came from the cache
+    Answered {
+        answers: [Option<String>; 2],
+        cached: bool,
+    },
+    MissingInput,
+    BuildFailed,
+    Panicked,
+}
+
+pub struct Run {
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Builds a day's crate in release mode and runs it against its input, passed along in
+/// `AOC_INPUT` for the solver to read through `aoc-common`.
+///
+/// Answers already in the cache for this input, these parameters and this build are
+/// served from there without running the solver.
+pub fn run_solver(solver: &Solver, root: &Path, parameters: &[String], cache: &Cache) -> Run {
+    let crate_dir = solver.crate_dir(root);
+    let not_run = |outcome| Run {
+        outcome,
+        elapsed: Duration::ZERO,
+    };
+
+    let build = Command::new("cargo")
+        .args(["build", "--release", "--quiet"])
+        .current_dir(&crate_dir)
+        .output()
+        .expect("couldn't run cargo");
+    if !build.status.success() {
+        return not_run(Outcome::BuildFailed);
+    }
+
+    let input = solver.input(root);
+    if !input.exists() {
+        return not_run(Outcome::MissingInput);
+    }
+
+    let keys = [1, 2].map(|part| Key {
+        year: solver.year,
+        day: solver.day,
+        part,
+        input: hash_file(&input),
+        parameters: hash_parameters(parameters),
+        build: hash_file(&solver.binary(root)),
+    });
+    let cached = [0, 1].map(|part| solver.parts[part].and_then(|_| cache.get(&keys[part])));
+    // Only the parts the solver reports need to be cached for it to count as a hit
+    if (0..2).all(|part| solver.parts[part].is_none() || cached[part].is_some()) {
+        return not_run(Outcome::Answered {
+            answers: cached,
+            cached: true,
+        });
+    }
+
+    let start = Instant::now();
+    let output = Command::new(solver.binary(root))
+        .args(parameters)
+        .current_dir(&crate_dir)
+        .env("AOC_INPUT", &input)
+        .output()
+        .expect("couldn't run the solver");
+    let elapsed = start.elapsed();
+
+    if !output.status.success() {
+        return Run {
+            outcome: Outcome::Panicked,
+            elapsed,
+        };
+    }
+
+    let mut values = reported_values(&String::from_utf8_lossy(&output.stdout));
+    values.extend(reported_values(&String::from_utf8_lossy(&output.stderr)));
+    let answers = [0, 1].map(|part| match solver.parts[part] {
+        Some(Reported::Labelled) => values.labelled[part].clone(),
+        Some(Reported::Value(index)) => values.unlabelled.get(index).cloned(),
+        None => None,
+    });
+    for (key, answer) in keys.iter().zip(&answers) {
+        if let Some(answer) = answer {
+            cache.put(key, answer);
+        }
+    }
+
+    Run {
+        outcome: Outcome::Answered {
+            answers,
+            cached: false,
+        },
+        elapsed,
+    }
+}
+
+/// The values a solver reported, in the order it reported them.
+#[derive(Debug, Default, PartialEq)]
+struct Values {
+    /// From `part 1:` and `part 2:` lines
+    labelled: [Option<String>; 2],
+    /// From `dbg!`
+    unlabelled: Vec<String>,
+}
+
+impl Values {
+    /// Adds values reported later, keeping the first answer printed for each part.
+    fn extend(&mut self, other: Values) {
+        for (mine, theirs) in self.labelled.iter_mut().zip(other.labelled) {
+            if mine.is_none() {
+                *mine = theirs;
+            }
+        }
+        self.unlabelled.extend(other.unlabelled);
+    }
+}
+
+/// Picks the answers out of a solver's output.
+///
+/// Solvers either `println!("part 1: ...")`, in which case the first number on the
+/// line is the answer, or `dbg!` the answer, in which case the value is everything
+/// after ` = `, with any `Some(...)` wrapper removed.
+fn reported_values(output: &str) -> Values {
+    let mut values = Values::default();
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("part ") {
+            if let Some((part @ ("1" | "2"), rest)) = rest.split_once(':') {
+                // The first run of digits, with a minus sign right before it
+                let digits = rest
+                    .find(|c: char| c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let sign = if rest[..digits].ends_with('-') {
+                    "-"
+                } else {
+                    ""
+                };
+                let number: String = sign
+                    .chars()
+                    .chain(rest[digits..].chars().take_while(|c| c.is_ascii_digit()))
+                    .collect();
+                let slot = &mut values.labelled[(part == "2") as usize];
+                slot.get_or_insert(number);
+            }
+        } else if line.starts_with("[src/") {
+            if let Some((_, value)) = line.split_once(" = ") {
+                let mut value = value.to_string();
+                // dbg! pretty-prints wrapped values across several lines
+                if value.ends_with('(') {
+                    for line in lines.by_ref() {
+                        value.push_str(line.trim());
+                        if line == ")" {
+                            break;
+                        }
+                    }
+                }
+                values.unlabelled.push(unwrap_some(&value));
+            }
+        }
+    }
+    values
+}
+
+fn unwrap_some(value: &str) -> String {
+    match value
+        .strip_prefix("Some(")
+        .and_then(|v| v.strip_suffix(')'))
+    {
+        Some(inner) => inner.trim_end_matches(',').to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+    use std::env;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn it_solves_the_input_in_the_inputs_directory() {
+        // A root with day 1's crate in it, but the example as the 2021 day 1 input
+        let repo = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let root = env::temp_dir().join(format!("aoc-runner-test-{}", std::process::id()));
+        fs::create_dir_all(root.join("inputs").join("2021")).unwrap();
+        for name in ["aoc2021-1", "aoc-common"] {
+            symlink(repo.join(name), root.join(name)).unwrap();
+        }
+        fs::write(
+            root.join("inputs").join("2021").join("1"),
+            "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n",
+        )
+        .unwrap();
+
+        let solver = registry::select(Some(2021), Some(1))[0];
+        let cache = Cache::new(root.join(".cache"), false);
+        let run = run_solver(solver, &root, &[], &cache);
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            run.outcome,
+            Outcome::Answered {
+                answers: [Some("7".to_string()), Some("5".to_string())],
+                cached: false,
+            }
+        );
+    }
+
+    #[test]
+    fn it_reads_println_answers() {
+        let output = r#"part 1: there were 7 increases
+part 2: there were 5 increases
+"#;
+        assert_eq!(
+            reported_values(output).labelled,
+            [Some("7".to_string()), Some("5".to_string())]
+        );
+        assert!(reported_values(output).unlabelled.is_empty());
+    }
+
+    #[test]
+    fn it_reads_dbg_answers() {
+        let output = r#"[src/main.rs:12:5] x = 15
+[src/main.rs:13:5] x as u32 * y as u32 = 150
+[src/main.rs:9:5] part_one = Some(
+    4512,
+)
+[src/main.rs:12:5] part_two = None
+"#;
+        assert_eq!(
+            reported_values(output).unlabelled,
+            vec!["15", "150", "4512", "None"]
+        );
+    }
+
+    #[test]
+    fn it_reads_labelled_answers_whatever_else_is_printed() {
+        let output = r#"[src/main.rs:12:5] width = 12
+part 2: 2990784 (0x2da2c0)
+part 1: 3813416 (0x3a30e8)
+part 3: 1
+"#;
+        let mut values = reported_values(output);
+        assert_eq!(
+            values.labelled,
+            [Some("3813416".to_string()), Some("2990784".to_string())]
+        );
+        assert_eq!(values.unlabelled, vec!["12"]);
+
+        // Anything on stderr comes after stdout
+        values.extend(reported_values("part 1: 5\n[src/main.rs:3:1] x = 4\n"));
+        assert_eq!(values.labelled[0], Some("3813416".to_string()));
+        assert_eq!(values.unlabelled, vec!["12", "4"]);
+    }
+
+    #[test]
+    fn it_reads_negative_labelled_answers() {
+        let output = "part 1: -42\npart 2: the answer is -7 - not 7\n";
+        assert_eq!(
+            reported_values(output).labelled,
+            [Some("-42".to_string()), Some("-7".to_string())]
+        );
+        assert_eq!(
+            reported_values("part 1: 3-4\n").labelled[0],
+            Some("3".to_string())
+        );
+    }
+
+    #[test]
+    fn it_ignores_other_output() {
+        let output = r#"40 x 6 Paper
+#....###..###
+"#;
+        assert_eq!(reported_values(output), Values::default());
+    }
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+
+use crate::report::{format_elapsed, Row, Star};
+
+const README_START: &str = "<!-- status:start -->";
+const README_END: &str = "<!-- status:end -->";
+
+fn format_table(rows: &[&Row]) -> String {
+    let mut output = "| Day | Part 1 | Part 2 | Time | Notes |\n".to_string();
+    output.push_str("| --: | :----: | :----: | ---: | ----- |\n");
+    for row in rows {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            row.day,
+            row.stars[0].symbol(),
+            row.stars[1].symbol(),
+            format_elapsed(row),
+            row.note
+        ));
+    }
+
+    let stars = rows
+        .iter()
+        .flat_map(|row| row.stars.iter())
+        .filter(|star| **star == Star::Verified)
+        .count();
+    output.push_str(&format!(
+        "\n{} of {} stars verified. ★ verified, ✗ wrong answer, ? unverified, · unsolved.\n",
+        stars,
+        rows.len() * 2
+    ));
+    output
+}
+
+/// One table per year, newest first.
+fn format_tables(rows: &[Row]) -> String {
+    let mut years: Vec<u16> = rows.iter().map(|row| row.year).collect();
+    years.sort_unstable();
+    years.dedup();
+
+    let mut output = "".to_string();
+    for year in years.iter().rev() {
+        let year_rows: Vec<&Row> = rows.iter().filter(|row| row.year == *year).collect();
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("### {}\n\n", year));
+        output.push_str(&format_table(&year_rows));
+    }
+    output
+}
+
+/// Replaces whatever sits between the status markers in the README, or appends the
+/// section if the markers aren't there yet.
+fn replace_readme_section(readme: &str, table: &str) -> String {
+    let section = format!("{}\n{}{}", README_START, table, README_END);
+    match (readme.find(README_START), readme.find(README_END)) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}{}{}",
+            &readme[..start],
+            section,
+            &readme[end + README_END.len()..]
+        ),
+        _ => format!("{}\n\n## Status\n\n{}\n", readme.trim_end(), section),
+    }
+}
+
+pub fn status(root: &Path, rows: &[Row], write_readme: bool) {
+    let tables = format_tables(rows);
+    print!("{}", tables);
+
+    if write_readme {
+        let path = root.join("README.md");
+        let readme = fs::read_to_string(&path).expect("couldn't read the README");
+        fs::write(&path, replace_readme_section(&readme, &tables))
+            .expect("couldn't write the README");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn row(year: u16, day: u8) -> Row {
+        Row {
+            year,
+            day,
+            values: [None, None],
+            stars: [Star::Verified, Star::Missing],
+            elapsed: None,
+            cached: false,
+            note: "",
+        }
+    }
+
+    #[test]
+    fn it_formats_a_table_per_year() {
+        let output = format_tables(&[row(2021, 1), row(2021, 2), row(2022, 1)]);
+        let headings: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("###"))
+            .collect();
+        assert_eq!(headings, vec!["### 2022", "### 2021"]);
+        assert!(output.contains("2 of 4 stars verified"));
+        assert!(output.contains("1 of 2 stars verified"));
+    }
+
+    #[test]
+    fn it_replaces_the_readme_section() {
+        let readme =
+            "# aoc2021\n\nintro\n\n<!-- status:start -->\nold\n<!-- status:end -->\n\nmore\n";
+        let output = replace_readme_section(readme, "new\n");
+        assert_eq!(
+            output,
+            "# aoc2021\n\nintro\n\n<!-- status:start -->\nnew\n<!-- status:end -->\n\nmore\n"
+        );
+    }
+
+    #[test]
+    fn it_appends_the_readme_section() {
+        let output = replace_readme_section("# aoc2021", "new\n");
+        assert_eq!(
+            output,
+            "# aoc2021\n\n## Status\n\n<!-- status:start -->\nnew\n<!-- status:end -->\n"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use aoc_common::checked::{Integer, Location, Overflow};
+
+/// Anything that can be summed into windows and compared. Sums can be a wider type
+/// than the readings, so large readings don't overflow.
+pub trait Depth: Integer + Default + Ord {}
+
+impl<T> Depth for T where T: Integer + Default + Ord {}
+
+/// Sums readings into sliding windows and counts how often a window's sum increases
+/// on the one `offset` windows before it.
+///
+/// The defaults are part one: windows of one reading, compared with their neighbour.
+/// Part two is the same with a `window` of 3.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthAnalyzer<S> {
+    /// How many readings go into each window
+    pub window: usize,
+    /// How many readings apart consecutive windows start
+    pub stride: usize,
+    /// How many windows back each window is compared with
+    pub offset: usize,
+    /// Increases smaller than this are ignored
+    pub min_delta: S,
+}
+
+impl<S: Depth> Default for DepthAnalyzer<S> {
+    fn default() -> Self {
+        DepthAnalyzer {
+            window: 1,
+            stride: 1,
+            offset: 1,
+            min_delta: S::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis<S> {
+    pub sums: Vec<S>,
+    pub increases: usize,
+}
+
+impl<S: Depth> DepthAnalyzer<S> {
+    /// Sums go through [`Location::add`], so with the `checked` feature a sum too big
+    /// for `S` is reported against the line of the reading that overflowed it.
+    pub fn analyze<T>(&self, readings: &[T]) -> Result<Analysis<S>, Overflow>
+    where
+        T: Copy + Into<S>,
+    {
+        assert!(
+            self.window > 0 && self.stride > 0 && self.offset > 0,
+            "window, stride and offset must all be at least 1"
+        );
+
+        let sums: Vec<S> = readings
+            .windows(self.window)
+            .enumerate()
+            .step_by(self.stride)
+            .map(|(start, slice)| {
+                slice
+                    .iter()
+                    .enumerate()
+                    .try_fold(S::default(), |sum, (offset, reading)| {
+                        Location::line(1, start + offset + 1).add(sum, (*reading).into())
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let increases = sums
+            .iter()
+            .zip(sums.iter().skip(self.offset))
+            .filter(|(before, after)| after > before && **after - **before >= self.min_delta)
+            .count();
+
+        Ok(Analysis { sums, increases })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [u16; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn it_matches_part_one() {
+        let analysis = DepthAnalyzer::<u16>::default().analyze(&INPUT).unwrap();
+        assert_eq!(analysis.increases, 7);
+        assert_eq!(analysis.sums, INPUT.to_vec());
+    }
+
+    #[test]
+    fn it_matches_part_two() {
+        let analyzer = DepthAnalyzer::<u16> {
+            window: 3,
+            ..DepthAnalyzer::default()
+        };
+        let analysis = analyzer.analyze(&INPUT).unwrap();
+        assert_eq!(analysis.increases, 5);
+        assert_eq!(analysis.sums, crate::stage_zero(&INPUT).unwrap());
+    }
+
+    #[test]
+    fn it_sums_into_a_wider_type() {
+        let analyzer = DepthAnalyzer::<u32> {
+            window: 3,
+            ..DepthAnalyzer::default()
+        };
+        let analysis = analyzer
+            .analyze(&[u16::MAX, u16::MAX, u16::MAX, u16::MAX])
+            .unwrap();
+        assert_eq!(analysis.sums, vec![196605, 196605]);
+        assert_eq!(analysis.increases, 0);
+    }
+
+    #[test]
+    fn it_strides_and_offsets_windows() {
+        let analyzer = DepthAnalyzer::<u16> {
+            window: 2,
+            stride: 2,
+            offset: 2,
+            ..DepthAnalyzer::default()
+        };
+        let analysis = analyzer.analyze(&INPUT).unwrap();
+        // [199, 200], [208, 210], [200, 207], [240, 269], [260, 263]
+        assert_eq!(analysis.sums, vec![399, 418, 407, 509, 523]);
+        // 407 > 399, 509 > 418, 523 > 407
+        assert_eq!(analysis.increases, 3);
+    }
+
+    #[test]
+    fn it_ignores_small_increases() {
+        let analyzer = DepthAnalyzer::<i64> {
+            min_delta: 5,
+            ..DepthAnalyzer::default()
+        };
+        let analysis = analyzer.analyze(&INPUT).unwrap();
+        // Only 200 -> 208, 200 -> 207, 207 -> 240 and 240 -> 269 clear the tolerance
+        assert_eq!(analysis.increases, 4);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflowing_sums() {
+        use aoc_common::checked::Operation;
+        let analyzer = DepthAnalyzer::<u16> {
+            window: 3,
+            ..DepthAnalyzer::default()
+        };
+        // The second window is the first to pass 65535, on its third reading
+        let readings: [u16; 4] = [1, 30000, 30000, 10000];
+        assert_eq!(
+            analyzer.analyze(&readings),
+            Err(Overflow {
+                day: 1,
+                operation: Operation::Add,
+                line: Some(4),
+            })
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::stream::{Counts, SonarStream};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    Unparsable {
+        line: usize,
+        text: String,
+    },
+    Spike {
+        line: usize,
+        value: u64,
+        mean: f64,
+        std_dev: f64,
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Anomaly::Unparsable { line, text } => {
+                write!(f, "line {}: couldn't parse {:?}", line, text)
+            }
+            Anomaly::Spike {
+                line,
+                value,
+                mean,
+                std_dev,
+            } => write!(
+                f,
+                "line {}: {} is a spike against a rolling mean of {:.1} (σ {:.1})",
+                line, value, mean, std_dev
+            ),
+        }
+    }
+}
+
+/// Flags readings more than `sigma` standard deviations from the mean of the
+/// `window` readings before them.
+///
+/// Every reading goes into the rolling window, spikes included, so after a real
+/// change in level the window catches up rather than flagging everything after it.
+/// Readings are whole numbers, so the deviation is never taken as less than 1, and
+/// nothing is flagged until the window has filled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyDetector {
+    pub window: usize,
+    pub sigma: f64,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        AnomalyDetector {
+            window: 10,
+            sigma: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalyReport {
+    pub anomalies: Vec<Anomaly>,
+    /// Increases across every reading that parsed
+    pub counts: Counts,
+    /// Increases across the readings that weren't flagged as spikes
+    pub clean_counts: Counts,
+}
+
+impl AnomalyDetector {
+    pub fn detect(&self, input: &str) -> AnomalyReport {
+        let mut anomalies = vec![];
+        let mut recent: VecDeque<u64> = VecDeque::with_capacity(self.window);
+        let mut all = SonarStream::new();
+        let mut clean = SonarStream::new();
+        let mut counts = Counts::default();
+        let mut clean_counts = Counts::default();
+
+        for (index, text) in input.lines().enumerate() {
+            let line = index + 1;
+            let value: u64 = match text.trim().parse() {
+                Ok(value) => value,
+                Err(_) => {
+                    anomalies.push(Anomaly::Unparsable {
+                        line,
+                        text: text.to_string(),
+                    });
+                    continue;
+                }
+            };
+            counts = all.push(value);
+
+            let mut spike = false;
+            if recent.len() == self.window {
+                let (mean, std_dev) = mean_and_std_dev(&recent);
+                if (value as f64 - mean).abs() > self.sigma * std_dev.max(1.0) {
+                    spike = true;
+                    anomalies.push(Anomaly::Spike {
+                        line,
+                        value,
+                        mean,
+                        std_dev,
+                    });
+                }
+                recent.pop_front();
+            }
+            recent.push_back(value);
+            if !spike {
+                clean_counts = clean.push(value);
+            }
+        }
+
+        AnomalyReport {
+            anomalies,
+            counts,
+            clean_counts,
+        }
+    }
+}
+
+fn mean_and_std_dev(values: &VecDeque<u64>) -> (f64, f64) {
+    let count = values.len() as f64;
+    let mean = values.iter().map(|value| *value as f64).sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (*value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+
+    #[test]
+    fn it_finds_nothing_wrong_with_the_example() {
+        let detector = AnomalyDetector {
+            window: 3,
+            sigma: 10.0,
+        };
+        let report = detector.detect(INPUT);
+        assert!(report.anomalies.is_empty());
+        assert_eq!(report.counts, report.clean_counts);
+        assert_eq!(
+            report.counts,
+            Counts {
+                part_one: 7,
+                part_two: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn it_reports_unparsable_lines() {
+        let input = "199\n200\n2O8\n\n210";
+        let report = AnomalyDetector::default().detect(input);
+        assert_eq!(
+            report.anomalies,
+            vec![
+                Anomaly::Unparsable {
+                    line: 3,
+                    text: "2O8".to_string(),
+                },
+                Anomaly::Unparsable {
+                    line: 4,
+                    text: "".to_string(),
+                },
+            ]
+        );
+        assert_eq!(report.counts.part_one, 2);
+        assert_eq!(
+            report.anomalies[0].to_string(),
+            "line 3: couldn't parse \"2O8\""
+        );
+    }
+
+    #[test]
+    fn it_flags_spikes() {
+        let input = "100\n102\n98\n101\n99\n9000\n98\n103";
+        let detector = AnomalyDetector {
+            window: 4,
+            sigma: 4.0,
+        };
+        let report = detector.detect(input);
+        assert_eq!(report.anomalies.len(), 1);
+        match &report.anomalies[0] {
+            Anomaly::Spike { line, value, .. } => {
+                assert_eq!(*line, 6);
+                assert_eq!(*value, 9000);
+            }
+            anomaly => panic!("expected a spike, got {:?}", anomaly),
+        }
+
+        // 102, 101, 9000 and 103 are increases with the spike, but only 102, 101
+        // and 103 without it
+        assert_eq!(report.counts.part_one, 4);
+        assert_eq!(report.clean_counts.part_one, 3);
+    }
+
+    #[test]
+    fn it_follows_a_change_in_level() {
+        let input = "100\n101\n100\n500\n501\n500\n501\n500";
+        let detector = AnomalyDetector {
+            window: 3,
+            sigma: 3.0,
+        };
+        let report = detector.detect(input);
+        // Only the jump itself stands out, and the window has caught up by line 5
+        let lines: Vec<usize> = report
+            .anomalies
+            .iter()
+            .map(|anomaly| match anomaly {
+                Anomaly::Spike { line, .. } => *line,
+                anomaly => panic!("expected a spike, got {:?}", anomaly),
+            })
+            .collect();
+        assert_eq!(lines, vec![4]);
+        // 101, 501 and 501 are increases without the jump
+        assert_eq!(report.clean_counts.part_one, 3);
+
+        // A steady window doesn't turn every small change into a spike
+        let report = detector.detect("5\n5\n5\n6\n5");
+        assert!(report.anomalies.is_empty());
+    }
+
+    #[test]
+    fn it_computes_rolling_statistics() {
+        let values: VecDeque<u64> = vec![2, 4, 4, 4, 5, 5, 7, 9].into();
+        assert_eq!(mean_and_std_dev(&values), (5.0, 2.0));
+    }
+}
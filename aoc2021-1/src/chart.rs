@@ -0,0 +1,156 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::events::Change;
+
+const WIDTH: f64 = 800.0;
+const PANEL_HEIGHT: f64 = 220.0;
+const MARGIN: f64 = 50.0;
+
+const STYLE: &str = r#"<style>
+  polyline { fill: none; stroke-width: 1.5; }
+  .increase { stroke: #2a9d4b; }
+  .decrease { stroke: #d1495b; }
+  .equal { stroke: #8d99ae; }
+  .highlight { fill: #f4d35e; fill-opacity: 0.35; }
+  text { font: 12px sans-serif; fill: #333; }
+</style>"#;
+
+/// Draws the raw depths above the window sums as an SVG line chart.
+///
+/// Both panels share one x axis, with each window sum drawn at the reading its window
+/// starts on, so a highlighted range of readings lines up across them. Depth grows
+/// downwards, like the submarine.
+pub fn render_chart(depths: &[u16], sums: &[u16], highlight: Option<&Range<usize>>) -> String {
+    let height = 2.0 * PANEL_HEIGHT + MARGIN;
+    let step = (WIDTH - 2.0 * MARGIN) / depths.len().saturating_sub(1).max(1) as f64;
+    let x = |index: usize| MARGIN + index as f64 * step;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        WIDTH, height, WIDTH, height
+    )
+    .unwrap();
+    writeln!(svg, "{}", STYLE).unwrap();
+
+    if let Some(range) = highlight {
+        writeln!(
+            svg,
+            r#"<rect class="highlight" x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}"/>"#,
+            x(range.start) - step / 2.0,
+            MARGIN / 2.0,
+            range.len() as f64 * step,
+            height - MARGIN
+        )
+        .unwrap();
+    }
+
+    panel(&mut svg, "depth", depths, MARGIN / 2.0, &x);
+    panel(&mut svg, "window sums", sums, MARGIN + PANEL_HEIGHT, &x);
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// One series, split into a polyline per run of increases, decreases or equal readings.
+fn panel(svg: &mut String, title: &str, values: &[u16], top: f64, x: &impl Fn(usize) -> f64) {
+    let plot_height = PANEL_HEIGHT - MARGIN;
+    let (min, max) = match (values.iter().min(), values.iter().max()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => return,
+    };
+    let scale = plot_height / (max - min).max(1) as f64;
+    let y = |value: u16| top + MARGIN / 2.0 + (value - min) as f64 * scale;
+
+    writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+        MARGIN,
+        top + 12.0,
+        title
+    )
+    .unwrap();
+    writeln!(svg, r#"<text x="4" y="{:.1}">{}</text>"#, y(min) + 4.0, min).unwrap();
+    writeln!(svg, r#"<text x="4" y="{:.1}">{}</text>"#, y(max) + 4.0, max).unwrap();
+
+    let changes: Vec<Change> = values
+        .windows(2)
+        .map(|pair| match pair[1].cmp(&pair[0]) {
+            Ordering::Greater => Change::Increase,
+            Ordering::Less => Change::Decrease,
+            Ordering::Equal => Change::Equal,
+        })
+        .collect();
+
+    let mut start = 0;
+    for run in changes.chunk_by(|a, b| a == b) {
+        let end = start + run.len();
+        let points: Vec<String> = (start..=end)
+            .map(|index| format!("{:.1},{:.1}", x(index), y(values[index])))
+            .collect();
+        writeln!(
+            svg,
+            r#"<polyline class="{}" points="{}"/>"#,
+            class(run[0]),
+            points.join(" ")
+        )
+        .unwrap();
+        start = end;
+    }
+}
+
+fn class(change: Change) -> &'static str {
+    match change {
+        Change::Increase => "increase",
+        Change::Decrease => "decrease",
+        Change::Equal => "equal",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [u16; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    #[test]
+    fn it_colours_each_run() {
+        let sums = crate::stage_zero(&INPUT).unwrap();
+        let svg = render_chart(&INPUT, &sums, None);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        // Depths go up, down, up, down, up, and the sums 607 618 618 617 647 716 769
+        // 792 go up, flat, down, up
+        assert_eq!(count(&svg, r#"<polyline class="increase""#), 3 + 2);
+        assert_eq!(count(&svg, r#"<polyline class="decrease""#), 2 + 1);
+        assert_eq!(count(&svg, r#"<polyline class="equal""#), 1);
+        assert_eq!(count(&svg, "<rect"), 0);
+    }
+
+    #[test]
+    fn it_highlights_a_range() {
+        let svg = render_chart(&INPUT, &[], Some(&(2..5)));
+        assert_eq!(count(&svg, r#"<rect class="highlight""#), 1);
+        // Readings are 700 / 9 apart, starting at the margin, and the highlight reaches
+        // half way to the readings either side
+        assert!(svg.contains(r#"x="166.7""#));
+        assert!(svg.contains(r#"width="233.3""#));
+    }
+
+    #[test]
+    fn it_draws_flat_and_tiny_series() {
+        let svg = render_chart(&[5, 5, 5], &[15], None);
+        assert_eq!(count(&svg, "<polyline"), 1);
+        assert!(svg.contains(r#"class="equal" points="50.0,50.0 400.0,50.0 750.0,50.0""#));
+
+        let svg = render_chart(&[], &[], None);
+        assert_eq!(count(&svg, "<polyline"), 0);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Increase,
+    Decrease,
+    Equal,
+}
+
+/// One reading compared with the reading before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    /// The 1-based input line of the new reading
+    pub line: usize,
+    pub previous: u64,
+    pub value: u64,
+    pub delta: i128,
+    pub change: Change,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} -> {} ({:+}) {:?}",
+            self.line, self.previous, self.value, self.delta, self.change
+        )
+    }
+}
+
+/// Run and plateau lengths are counted in steps between readings, so two equal
+/// readings in a row are a plateau of 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub increases: usize,
+    pub decreases: usize,
+    pub equals: usize,
+    /// The most increases in a row
+    pub longest_ascent: usize,
+    /// The most decreases in a row
+    pub longest_descent: usize,
+    /// The length of every stretch of equal readings, in input order
+    pub plateaus: Vec<usize>,
+    /// The event with the biggest change in either direction
+    pub largest_jump: Option<Event>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub events: Vec<Event>,
+    pub summary: Summary,
+}
+
+pub fn depth_events(readings: &[u64]) -> Report {
+    let events: Vec<Event> = readings
+        .windows(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let delta = pair[1] as i128 - pair[0] as i128;
+            Event {
+                line: index + 2,
+                previous: pair[0],
+                value: pair[1],
+                delta,
+                change: match delta {
+                    d if d > 0 => Change::Increase,
+                    d if d < 0 => Change::Decrease,
+                    _ => Change::Equal,
+                },
+            }
+        })
+        .collect();
+
+    let summary = Summary {
+        increases: count(&events, Change::Increase),
+        decreases: count(&events, Change::Decrease),
+        equals: count(&events, Change::Equal),
+        longest_ascent: runs(&events, Change::Increase).max().unwrap_or(0),
+        longest_descent: runs(&events, Change::Decrease).max().unwrap_or(0),
+        plateaus: runs(&events, Change::Equal).collect(),
+        largest_jump: events.iter().max_by_key(|event| event.delta.abs()).copied(),
+    };
+
+    Report { events, summary }
+}
+
+fn count(events: &[Event], change: Change) -> usize {
+    events.iter().filter(|event| event.change == change).count()
+}
+
+/// The lengths of each unbroken run of the given change.
+fn runs(events: &[Event], change: Change) -> impl Iterator<Item = usize> + '_ {
+    events
+        .split(move |event| event.change != change)
+        .map(|run| run.len())
+        .filter(|length| *length > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [u64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn it_records_each_change() {
+        let report = depth_events(&INPUT);
+        assert_eq!(report.events.len(), 9);
+        assert_eq!(
+            report.events[3],
+            Event {
+                line: 5,
+                previous: 210,
+                value: 200,
+                delta: -10,
+                change: Change::Decrease,
+            }
+        );
+        assert_eq!(
+            report.events[3].to_string(),
+            "line 5: 210 -> 200 (-10) Decrease"
+        );
+    }
+
+    #[test]
+    fn it_summarizes_the_profile() {
+        let report = depth_events(&INPUT);
+        assert_eq!(report.summary.increases, 7);
+        assert_eq!(report.summary.decreases, 2);
+        assert_eq!(report.summary.equals, 0);
+        assert_eq!(report.summary.longest_ascent, 3);
+        assert_eq!(report.summary.longest_descent, 1);
+        assert!(report.summary.plateaus.is_empty());
+        assert_eq!(report.summary.largest_jump.unwrap().line, 7);
+    }
+
+    #[test]
+    fn it_finds_plateaus_and_descents() {
+        let report = depth_events(&[5, 5, 5, 4, 3, 2, 2, 9]);
+        assert_eq!(report.summary.plateaus, vec![2, 1]);
+        assert_eq!(report.summary.longest_descent, 3);
+        assert_eq!(report.summary.longest_ascent, 1);
+        assert_eq!(report.summary.largest_jump.unwrap().delta, 7);
+    }
+
+    #[test]
+    fn it_handles_too_few_readings() {
+        let report = depth_events(&[100]);
+        assert!(report.events.is_empty());
+        assert_eq!(report.summary.longest_ascent, 0);
+        assert_eq!(report.summary.largest_jump, None);
+    }
+}
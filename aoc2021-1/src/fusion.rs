@@ -0,0 +1,224 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How the readings at one index are combined into a single depth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Consensus {
+    /// The middle reading, or the mean of the middle two rounded down
+    Median,
+    /// The mean reading, rounded down
+    Mean,
+}
+
+impl FromStr for Consensus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "median" => Ok(Consensus::Median),
+            "mean" => Ok(Consensus::Mean),
+            _ => Err(format!("unknown consensus {:?}", s)),
+        }
+    }
+}
+
+impl Consensus {
+    fn combine(&self, readings: &mut [u16]) -> u16 {
+        match self {
+            Consensus::Median => {
+                readings.sort_unstable();
+                let middle = readings.len() / 2;
+                if readings.len() % 2 == 1 {
+                    readings[middle]
+                } else {
+                    ((readings[middle - 1] as u32 + readings[middle] as u32) / 2) as u16
+                }
+            }
+            Consensus::Mean => {
+                let sum: u64 = readings.iter().map(|reading| *reading as u64).sum();
+                (sum / readings.len() as u64) as u16
+            }
+        }
+    }
+}
+
+/// An index where the channels' readings are further apart than the threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement {
+    /// The 1-based line the readings came from in each channel
+    pub line: usize,
+    /// Every channel's reading, or `None` if it had already ended
+    pub readings: Vec<Option<u16>>,
+    /// The highest reading minus the lowest
+    pub spread: u16,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let readings: Vec<String> = self
+            .readings
+            .iter()
+            .map(|reading| match reading {
+                Some(reading) => reading.to_string(),
+                None => "-".to_string(),
+            })
+            .collect();
+        write!(
+            f,
+            "line {}: channels disagree by {} ({})",
+            self.line,
+            self.spread,
+            readings.join(", ")
+        )
+    }
+}
+
+/// Combines several channels of readings, aligned by index, into one series.
+///
+/// The fused series is as long as the longest channel. Once a shorter channel ends,
+/// the remaining channels carry on without it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fusion {
+    pub consensus: Consensus,
+    /// Spreads bigger than this are reported
+    pub threshold: u16,
+}
+
+impl Default for Fusion {
+    fn default() -> Self {
+        Fusion {
+            consensus: Consensus::Median,
+            threshold: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fused {
+    pub depths: Vec<u16>,
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl Fusion {
+    pub fn fuse(&self, channels: &[Vec<u16>]) -> Fused {
+        let length = channels
+            .iter()
+            .map(|channel| channel.len())
+            .max()
+            .unwrap_or(0);
+        let mut depths = Vec::with_capacity(length);
+        let mut disagreements = vec![];
+
+        for index in 0..length {
+            let readings: Vec<Option<u16>> = channels
+                .iter()
+                .map(|channel| channel.get(index).copied())
+                .collect();
+            let mut present: Vec<u16> = readings.iter().flatten().copied().collect();
+
+            let spread = present.iter().max().unwrap() - present.iter().min().unwrap();
+            if spread > self.threshold {
+                disagreements.push(Disagreement {
+                    line: index + 1,
+                    readings,
+                    spread,
+                });
+            }
+            depths.push(self.consensus.combine(&mut present));
+        }
+
+        Fused {
+            depths,
+            disagreements,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: [u16; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn it_agrees_with_a_single_channel() {
+        let fused = Fusion::default().fuse(&[INPUT.to_vec()]);
+        assert_eq!(fused.depths, INPUT.to_vec());
+        assert!(fused.disagreements.is_empty());
+    }
+
+    #[test]
+    fn it_outvotes_a_faulty_channel() {
+        let mut faulty = INPUT.to_vec();
+        faulty[4] = 9000;
+        let fused = Fusion::default().fuse(&[INPUT.to_vec(), faulty, INPUT.to_vec()]);
+        assert_eq!(fused.depths, INPUT.to_vec());
+        assert_eq!(
+            fused.disagreements,
+            vec![Disagreement {
+                line: 5,
+                readings: vec![Some(200), Some(9000), Some(200)],
+                spread: 8800,
+            }]
+        );
+        assert_eq!(
+            fused.disagreements[0].to_string(),
+            "line 5: channels disagree by 8800 (200, 9000, 200)"
+        );
+
+        // The same channels through stage one and two
+        assert_eq!(crate::stage_one(&fused.depths).len(), 7);
+        assert_eq!(
+            crate::stage_one(&crate::stage_zero(&fused.depths).unwrap()).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn it_combines_readings() {
+        let channels = vec![vec![100, 7], vec![104, 9], vec![101], vec![u16::MAX]];
+        let median = Fusion {
+            consensus: Consensus::Median,
+            threshold: u16::MAX,
+        };
+        // 100, 101, 104, 65535 has 101 and 104 in the middle; 7 and 9 average to 8
+        assert_eq!(median.fuse(&channels).depths, vec![102, 8]);
+
+        let mean = Fusion {
+            consensus: Consensus::Mean,
+            ..median
+        };
+        // The sum of 65840 wouldn't fit in a u16, but the mean does
+        assert_eq!(mean.fuse(&channels).depths, vec![16460, 8]);
+    }
+
+    #[test]
+    fn it_carries_on_when_a_channel_ends() {
+        let fusion = Fusion {
+            threshold: 1,
+            ..Fusion::default()
+        };
+        let fused = fusion.fuse(&[vec![5, 6, 7], vec![5, 9]]);
+        assert_eq!(fused.depths, vec![5, 7, 7]);
+        assert_eq!(fused.disagreements.len(), 1);
+        assert_eq!(fused.disagreements[0].line, 2);
+        assert_eq!(
+            fused.disagreements[0].to_string(),
+            "line 2: channels disagree by 3 (6, 9)"
+        );
+
+        let fused = fusion.fuse(&[vec![1, 1, 1], vec![1, 1, 9], vec![1]]);
+        assert_eq!(
+            fused.disagreements[0].to_string(),
+            "line 3: channels disagree by 8 (1, 9, -)"
+        );
+        assert_eq!(Fusion::default().fuse(&[]).depths, vec![]);
+    }
+
+    #[test]
+    fn it_parses_the_consensus() {
+        assert_eq!("median".parse(), Ok(Consensus::Median));
+        assert_eq!("mean".parse(), Ok(Consensus::Mean));
+        assert!("mode".parse::<Consensus>().is_err());
+    }
+}
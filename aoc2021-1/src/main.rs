@@ -1,9 +1,33 @@
+mod analyzer;
+mod anomaly;
+mod chart;
+mod events;
+mod fusion;
+mod stream;
+
+use analyzer::DepthAnalyzer;
+use anomaly::AnomalyDetector;
+use aoc_common::checked::{self, Integer, Location, Overflow};
+use aoc_common::input;
+use fusion::Fusion;
+use std::env;
 use std::fs;
+use std::io;
+use std::ops::Range;
+use std::process;
+
+const USAGE: &str = r#"usage:
+    aoc2021-1
+    aoc2021-1 [--window N] [--stride N] [--offset N] [--min-delta N]
+    aoc2021-1 --events
+    aoc2021-1 --anomalies [--window N] [--sigma X]
+    aoc2021-1 --render PATH [--highlight START..END]
+    aoc2021-1 --fuse FILE... [--consensus median|mean] [--threshold N]
+    aoc2021-1 --stream < readings"#;
 
 fn stage_one(input: &[u16]) -> Vec<u16> {
     input
         .windows(2)
-        .into_iter()
         .filter_map(|slice| {
             if let [first, second] = slice {
                 if second > first {
@@ -18,25 +42,265 @@ fn stage_one(input: &[u16]) -> Vec<u16> {
         .collect()
 }
 
-fn stage_zero(input: &[u16]) -> Vec<u16> {
+fn stage_zero(input: &[u16]) -> Result<Vec<u16>, Overflow> {
     input
         .windows(3)
-        .into_iter()
-        .map(|slice| slice.iter().sum::<u16>())
+        .enumerate()
+        .map(|(start, slice)| {
+            slice
+                .iter()
+                .enumerate()
+                .try_fold(0, |sum, (offset, value)| {
+                    Location::line(1, start + offset + 1).add(sum, *value)
+                })
+        })
         .collect()
 }
 
-fn main() {
-    let input: Vec<u16> = fs::read_to_string("input")
-        .expect("couldn't read the file")
-        .lines()
-        .map(|line| line.parse().expect("couldn't parse number"))
+fn read_input<T: Integer>() -> Vec<T> {
+    parse_lines(&input::read_input()).expect("couldn't read the readings")
+}
+
+fn read_file<T: Integer>(path: &str) -> Vec<T> {
+    let text = fs::read_to_string(path).unwrap_or_else(|_| panic!("couldn't read {}", path));
+    parse_lines(&text).unwrap_or_else(|error| panic!("couldn't read {}: {}", path, error))
+}
+
+/// One reading per line. A line that isn't a number, or is too big for `T`, is an
+/// error naming the line.
+fn parse_lines<T: Integer>(text: &str) -> Result<Vec<T>, checked::Error> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| Location::line(1, index + 1).parse(line, 10))
+        .collect()
+}
+
+/// Reads `--window`, `--stride`, `--offset` and `--min-delta` over the part one defaults.
+fn parse_analyzer(args: &[String]) -> Option<DepthAnalyzer<u64>> {
+    let mut analyzer = DepthAnalyzer::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value: u64 = iter.next()?.parse().ok()?;
+        match arg.as_str() {
+            "--window" => analyzer.window = value as usize,
+            "--stride" => analyzer.stride = value as usize,
+            "--offset" => analyzer.offset = value as usize,
+            "--min-delta" => analyzer.min_delta = value,
+            _ => return None,
+        }
+    }
+
+    if analyzer.window == 0 || analyzer.stride == 0 || analyzer.offset == 0 {
+        return None;
+    }
+    Some(analyzer)
+}
+
+/// Reads `--window` and `--sigma` over the detector's defaults.
+fn parse_detector(args: &[String]) -> Option<AnomalyDetector> {
+    let mut detector = AnomalyDetector::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next()?;
+        match arg.as_str() {
+            "--window" => detector.window = value.parse().ok()?,
+            "--sigma" => detector.sigma = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+
+    if detector.window == 0 || detector.sigma.is_nan() || detector.sigma < 0.0 {
+        return None;
+    }
+    Some(detector)
+}
+
+/// Reads `--render PATH` and an optional `--highlight START..END`, in either order.
+fn parse_render(args: &[String]) -> Option<(String, Option<Range<usize>>)> {
+    let mut path = None;
+    let mut highlight = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next()?;
+        match arg.as_str() {
+            "--render" => path = Some(value.to_string()),
+            "--highlight" => {
+                let (start, end) = value.split_once("..")?;
+                let range = start.parse().ok()?..end.parse().ok()?;
+                if range.is_empty() {
+                    return None;
+                }
+                highlight = Some(range);
+            }
+            _ => return None,
+        }
+    }
+    Some((path?, highlight))
+}
+
+/// Reads the channel files after `--fuse`, then `--consensus` and `--threshold` over
+/// the fusion defaults.
+fn parse_fusion(args: &[String]) -> Option<(Vec<String>, Fusion)> {
+    let paths: Vec<String> = args
+        .iter()
+        .take_while(|arg| !arg.starts_with("--"))
+        .cloned()
         .collect();
+    if paths.is_empty() {
+        return None;
+    }
+
+    let mut fusion = Fusion::default();
+    let mut iter = args[paths.len()..].iter();
+    while let Some(arg) = iter.next() {
+        let value = iter.next()?;
+        match arg.as_str() {
+            "--consensus" => fusion.consensus = value.parse().ok()?,
+            "--threshold" => fusion.threshold = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+    Some((paths, fusion))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        None => parts(),
+        Some("--stream") if args.len() == 1 => stream(),
+        Some("--events") if args.len() == 1 => report_events(),
+        Some("--render") | Some("--highlight") => match parse_render(&args) {
+            Some((path, highlight)) => {
+                parts();
+                render(&path, highlight);
+            }
+            None => usage(),
+        },
+        Some("--fuse") => match parse_fusion(&args[1..]) {
+            Some((paths, fusion)) => fuse(&paths, &fusion),
+            None => usage(),
+        },
+        Some("--anomalies") => match parse_detector(&args[1..]) {
+            Some(detector) => report_anomalies(&detector),
+            None => usage(),
+        },
+        Some(_) => match parse_analyzer(&args) {
+            Some(analyzer) => analyze(&analyzer),
+            None => usage(),
+        },
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn analyze(analyzer: &DepthAnalyzer<u64>) {
+    let readings: Vec<u64> = read_input();
+
+    let analysis = analyzer
+        .analyze(&readings)
+        .expect("couldn't sum the windows");
+    for sum in analysis.sums {
+        println!("{}", sum);
+    }
+    println!("there were {} increases", analysis.increases);
+}
+
+fn report_events() {
+    let readings: Vec<u64> = read_input();
+
+    let report = events::depth_events(&readings);
+    for event in &report.events {
+        println!("{}", event);
+    }
+
+    let summary = report.summary;
+    println!(
+        "{} increases, {} decreases, {} unchanged",
+        summary.increases, summary.decreases, summary.equals
+    );
+    println!("longest ascent: {} steps", summary.longest_ascent);
+    println!("longest descent: {} steps", summary.longest_descent);
+    println!("plateaus: {:?}", summary.plateaus);
+    if let Some(jump) = summary.largest_jump {
+        println!("largest jump: {}", jump);
+    }
+}
+
+/// Reports bad lines and spikes, where a plain run would panic or be thrown off.
+fn report_anomalies(detector: &AnomalyDetector) {
+    let input = input::read_input();
+    let report = detector.detect(&input);
+    for anomaly in &report.anomalies {
+        println!("{}", anomaly);
+    }
+    println!(
+        "with every reading: {} and {} increases",
+        report.counts.part_one, report.counts.part_two
+    );
+    println!(
+        "without spikes: {} and {} increases",
+        report.clean_counts.part_one, report.clean_counts.part_two
+    );
+}
+
+fn render(path: &str, highlight: Option<Range<usize>>) {
+    let depths: Vec<u16> = read_input();
+    if let Some(range) = &highlight {
+        if range.end > depths.len() {
+            eprintln!(
+                "can't highlight {:?}, there are only {} readings",
+                range,
+                depths.len()
+            );
+            process::exit(2);
+        }
+    }
+
+    let sums = stage_zero(&depths).expect("couldn't sum the windows");
+    let svg = chart::render_chart(&depths, &sums, highlight.as_ref());
+    fs::write(path, svg).expect("couldn't write the chart");
+    eprintln!("wrote the depth chart to {}", path);
+}
+
+/// Fuses several channel files into one series and counts its increases.
+fn fuse(paths: &[String], fusion: &Fusion) {
+    let channels: Vec<Vec<u16>> = paths.iter().map(|path| read_file(path)).collect();
+    let fused = fusion.fuse(&channels);
+
+    for (path, channel) in paths.iter().zip(&channels) {
+        if channel.len() < fused.depths.len() {
+            println!("{} ends after {} readings", path, channel.len());
+        }
+    }
+    for disagreement in &fused.disagreements {
+        println!("{}", disagreement);
+    }
+
+    let first = stage_one(&fused.depths);
+    let sums = stage_zero(&fused.depths).expect("couldn't sum the windows");
+    let second = stage_one(&sums);
+    println!("fused: {} and {} increases", first.len(), second.len());
+}
+
+/// Prints the running part one and part two counts after each reading on stdin.
+fn stream() {
+    let stdin = io::stdin();
+    for counts in stream::running_counts(stream::read_readings(stdin.lock())) {
+        println!("{} {}", counts.part_one, counts.part_two);
+    }
+}
+
+fn parts() {
+    let input: Vec<u16> = read_input();
 
     let first = stage_one(&input);
     println!("part 1: there were {} increases", first.len());
 
-    let second = stage_one(&stage_zero(&input));
+    let sums = stage_zero(&input).expect("couldn't sum the windows");
+    let second = stage_one(&sums);
     println!("part 2: there were {} increases", second.len());
 }
 
@@ -53,7 +317,134 @@ mod tests {
     #[test]
     fn stage_zero_works() {
         let input: Vec<u16> = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-        let output = stage_one(&stage_zero(&input));
+        let output = stage_one(&stage_zero(&input).unwrap());
         assert_eq!(output.len(), 5);
     }
+
+    #[test]
+    fn it_parses_analyzer_args() {
+        let args: Vec<String> = "--window 3 --min-delta 5"
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect();
+        let analyzer = parse_analyzer(&args).unwrap();
+        assert_eq!(analyzer.window, 3);
+        assert_eq!(analyzer.stride, 1);
+        assert_eq!(analyzer.offset, 1);
+        assert_eq!(analyzer.min_delta, 5);
+
+        assert_eq!(parse_analyzer(&["--window".to_string()]), None);
+        assert_eq!(
+            parse_analyzer(&["--window".to_string(), "0".to_string()]),
+            None
+        );
+        assert_eq!(
+            parse_analyzer(&["--depth".to_string(), "3".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn it_parses_detector_args() {
+        let args: Vec<String> = "--sigma 2.5 --window 20"
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect();
+        let detector = parse_detector(&args).unwrap();
+        assert_eq!(detector.window, 20);
+        assert_eq!(detector.sigma, 2.5);
+
+        assert_eq!(parse_detector(&[]), Some(AnomalyDetector::default()));
+        assert_eq!(
+            parse_detector(&["--sigma".to_string(), "-1".to_string()]),
+            None
+        );
+    }
+
+    #[test]
+    fn it_parses_render_args() {
+        let args: Vec<String> = "--highlight 10..20 --render out.svg"
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            parse_render(&args),
+            Some(("out.svg".to_string(), Some(10..20)))
+        );
+        assert_eq!(
+            parse_render(&args[2..]),
+            Some(("out.svg".to_string(), None))
+        );
+
+        // A highlight needs somewhere to go, and something in it
+        assert_eq!(parse_render(&args[..2]), None);
+        assert_eq!(
+            parse_render(&[
+                "--render".to_string(),
+                "out.svg".to_string(),
+                "--highlight".to_string(),
+                "20..10".to_string()
+            ]),
+            None
+        );
+    }
+
+    #[test]
+    fn it_parses_fusion_args() {
+        let args: Vec<String> = "a b c --threshold 25 --consensus mean"
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect();
+        let (paths, fusion) = parse_fusion(&args).unwrap();
+        assert_eq!(paths, vec!["a", "b", "c"]);
+        assert_eq!(fusion.threshold, 25);
+        assert_eq!(fusion.consensus, fusion::Consensus::Mean);
+
+        assert_eq!(parse_fusion(&args[3..]), None);
+        assert_eq!(parse_fusion(&args[..4]), None);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn stage_zero_reports_overflow() {
+        use aoc_common::checked::Operation;
+        let input: Vec<u16> = vec![199, 200, 65500, 210, 200];
+        let output = stage_zero(&input);
+        assert_eq!(
+            output,
+            Err(Overflow {
+                day: 1,
+                operation: Operation::Add,
+                line: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn it_reports_readings_that_arent_numbers() {
+        use aoc_common::checked::Invalid;
+        assert_eq!(parse_lines::<u16>("199\n200\n208"), Ok(vec![199, 200, 208]));
+        assert_eq!(
+            parse_lines::<u16>("199\n2oo\n208"),
+            Err(checked::Error::Invalid(Invalid {
+                day: 1,
+                text: "2oo".to_string(),
+                line: Some(2),
+            }))
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflowing_readings() {
+        use aoc_common::checked::Operation;
+        assert_eq!(
+            parse_lines::<u16>("199\n200\n70000"),
+            Err(checked::Error::Overflow(Overflow {
+                day: 1,
+                operation: Operation::Parse,
+                line: Some(3),
+            }))
+        );
+    }
 }
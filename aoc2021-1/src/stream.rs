@@ -0,0 +1,143 @@
+use std::io::BufRead;
+
+/// Increases counted so far, for both parts.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Counts {
+    pub part_one: usize,
+    pub part_two: usize,
+}
+
+/// Counts increases one reading at a time, keeping only the last three readings.
+///
+/// Consecutive 3-reading windows share two readings, so one window's sum is bigger
+/// than the last exactly when the reading entering it is bigger than the reading
+/// leaving it. That means part two never has to sum anything.
+#[derive(Debug)]
+pub struct SonarStream<T> {
+    /// The last three readings, oldest first
+    recent: [Option<T>; 3],
+    counts: Counts,
+}
+
+impl<T: Copy + PartialOrd> SonarStream<T> {
+    pub fn new() -> Self {
+        SonarStream {
+            recent: [None, None, None],
+            counts: Counts::default(),
+        }
+    }
+
+    pub fn push(&mut self, reading: T) -> Counts {
+        if let Some(previous) = self.recent[2] {
+            if reading > previous {
+                self.counts.part_one += 1;
+            }
+        }
+        if let Some(leaving) = self.recent[0] {
+            if reading > leaving {
+                self.counts.part_two += 1;
+            }
+        }
+
+        self.recent.rotate_left(1);
+        self.recent[2] = Some(reading);
+        self.counts
+    }
+}
+
+impl<T: Copy + PartialOrd> Default for SonarStream<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The running counts after each reading.
+pub fn running_counts<T, I>(readings: I) -> impl Iterator<Item = Counts>
+where
+    T: Copy + PartialOrd,
+    I: IntoIterator<Item = T>,
+{
+    let mut stream = SonarStream::new();
+    readings
+        .into_iter()
+        .map(move |reading| stream.push(reading))
+}
+
+/// Readings from a reader, one per line, without loading the whole thing.
+pub fn read_readings<R: BufRead>(reader: R) -> impl Iterator<Item = u64> {
+    reader.lines().map(|line| {
+        line.expect("couldn't read the line")
+            .trim()
+            .parse()
+            .expect("couldn't parse number")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    #[test]
+    fn it_counts_both_parts_in_one_pass() {
+        let counts = running_counts(read_readings(INPUT.as_bytes())).last();
+        assert_eq!(
+            counts,
+            Some(Counts {
+                part_one: 7,
+                part_two: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn it_emits_running_counts() {
+        let counts: Vec<Counts> = running_counts(read_readings(INPUT.as_bytes())).collect();
+        assert_eq!(counts.len(), 10);
+        assert_eq!(counts[0], Counts::default());
+        // 200 > 199 and 208 > 200, but there's only one full window so far
+        assert_eq!(
+            counts[2],
+            Counts {
+                part_one: 2,
+                part_two: 0,
+            }
+        );
+        // 210 > 199, so the second window beats the first
+        assert_eq!(
+            counts[3],
+            Counts {
+                part_one: 3,
+                part_two: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn it_agrees_with_the_stages() {
+        let input: Vec<u16> = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let mut stream = SonarStream::new();
+        let mut counts = Counts::default();
+        for reading in &input {
+            counts = stream.push(*reading);
+        }
+        assert_eq!(counts.part_one, crate::stage_one(&input).len());
+        assert_eq!(
+            counts.part_two,
+            crate::stage_one(&crate::stage_zero(&input).unwrap()).len()
+        );
+    }
+
+    #[test]
+    fn it_never_sums_large_readings() {
+        let counts = running_counts([u64::MAX - 1, u64::MAX, u64::MAX, u64::MAX]).last();
+        assert_eq!(
+            counts,
+            Some(Counts {
+                part_one: 1,
+                part_two: 1,
+            })
+        );
+    }
+}
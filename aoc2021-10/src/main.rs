@@ -1,41 +1,48 @@
-use std::fs;
+use aoc_common::checked::{Location, Overflow};
+use aoc_common::input::read_input;
 
 fn main() {
-    let subsystem: Vec<String> = fs::read_to_string("input")
-        .expect("Couldn't read the input")
+    let subsystem: Vec<String> = read_input()
         .lines()
         .map(|line| line.to_string())
         .collect();
 
-    let error_score = score_subsystem(&subsystem);
+    let error_score = score_subsystem(&subsystem).expect("couldn't score the subsystem");
     dbg!(error_score);
 
-    let mut completion_scores = score_completions(&subsystem);
+    let mut completion_scores =
+        score_completions(&subsystem).expect("couldn't score the completions");
     completion_scores.sort_unstable();
     let middle = (completion_scores.len() as f32 / 2.0).floor() as usize;
     dbg!(completion_scores[middle]);
 }
 
-fn score_subsystem(input: &[String]) -> u64 {
-    input.iter().map(|line| score_line(line)).sum::<u64>()
+fn score_subsystem(input: &[String]) -> Result<u64, Overflow> {
+    input
+        .iter()
+        .enumerate()
+        .try_fold(0, |total, (index, line)| {
+            Location::line(10, index + 1).add(total, score_line(line))
+        })
 }
 
-fn score_completions(input: &[String]) -> Vec<u64> {
+fn score_completions(input: &[String]) -> Result<Vec<u64>, Overflow> {
     let mut output: Vec<u64> = vec![];
 
-    for line in input {
+    for (index, line) in input.iter().enumerate() {
         // Drop invalid lines
         if score_line(line) != 0 {
             continue;
         }
 
         if let Some(suggestions) = suggest_completions(line) {
-            let suggestion_score = score_completion_suggestions(&suggestions);
+            let suggestion_score =
+                score_completion_suggestions(&suggestions, Location::line(10, index + 1))?;
             output.push(suggestion_score);
         }
     }
 
-    output
+    Ok(output)
 }
 
 fn score_line(line: &str) -> u64 {
@@ -120,7 +127,7 @@ fn suggest_completions(line: &str) -> Option<String> {
     Some(output)
 }
 
-fn score_completion_suggestions(input: &str) -> u64 {
+fn score_completion_suggestions(input: &str, at: Location) -> Result<u64, Overflow> {
     input
         .chars()
         .map(|c| match c {
@@ -130,7 +137,7 @@ fn score_completion_suggestions(input: &str) -> u64 {
             '>' => 4,
             _ => unimplemented!("Symbol not handled"),
         })
-        .fold(0, |acc, x| acc * 5 + x)
+        .try_fold(0, |acc, x| at.add(at.mul(acc, 5)?, x))
 }
 
 #[cfg(test)]
@@ -198,7 +205,7 @@ mod tests {
     fn it_scores_subsystems() {
         let input: Vec<String> = SUBSYSTEM.lines().map(|line| line.to_string()).collect();
         let output = score_subsystem(&input);
-        assert_eq!(output, 26397);
+        assert_eq!(output, Ok(26397));
     }
 
     #[test]
@@ -227,24 +234,24 @@ mod tests {
     #[test]
     fn it_scores_completions() {
         let input = "}}]])})]".to_string();
-        let output = score_completion_suggestions(&input);
-        assert_eq!(output, 288957);
+        let output = score_completion_suggestions(&input, Location::line(10, 1));
+        assert_eq!(output, Ok(288957));
 
         let input = ")}>]})".to_string();
-        let output = score_completion_suggestions(&input);
-        assert_eq!(output, 5566);
+        let output = score_completion_suggestions(&input, Location::line(10, 1));
+        assert_eq!(output, Ok(5566));
 
         let input = "}}>}>))))".to_string();
-        let output = score_completion_suggestions(&input);
-        assert_eq!(output, 1480781);
+        let output = score_completion_suggestions(&input, Location::line(10, 1));
+        assert_eq!(output, Ok(1480781));
 
         let input = "]]}}]}]}>".to_string();
-        let output = score_completion_suggestions(&input);
-        assert_eq!(output, 995444);
+        let output = score_completion_suggestions(&input, Location::line(10, 1));
+        assert_eq!(output, Ok(995444));
 
         let input = "])}>".to_string();
-        let output = score_completion_suggestions(&input);
-        assert_eq!(output, 294);
+        let output = score_completion_suggestions(&input, Location::line(10, 1));
+        assert_eq!(output, Ok(294));
     }
 
     #[test]
@@ -255,9 +262,24 @@ mod tests {
             .map(|value| value.to_string())
             .collect();
 
-        let mut scored_suggestions = score_completions(&input);
+        let mut scored_suggestions = score_completions(&input).unwrap();
         scored_suggestions.sort_unstable();
         let middle = (scored_suggestions.len() as f32 / 2.0).floor() as usize;
         assert_eq!(scored_suggestions[middle], 288957);
     }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflowing_completion_scores() {
+        use aoc_common::checked::Operation;
+        let input = vec!["[]".to_string(), "<".repeat(30)];
+        assert_eq!(
+            score_completions(&input),
+            Err(Overflow {
+                day: 10,
+                operation: Operation::Mul,
+                line: Some(2),
+            })
+        );
+    }
 }
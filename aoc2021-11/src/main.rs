@@ -1,9 +1,8 @@
+use aoc_common::input::read_input;
 use std::collections::HashMap;
-use std::fs;
 
 fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("Couldn't read the input")
+    let input: Vec<String> = read_input()
         .lines()
         .map(|line| line.to_string())
         .collect();
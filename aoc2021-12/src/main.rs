@@ -1,13 +1,12 @@
+use aoc_common::input::read_input;
 use std::collections::HashMap;
-use std::fs;
 
 type CaveGraph = HashMap<String, Vec<String>>;
 type Edge = Vec<String>;
 type Path = String;
 
 fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("Couldn't read the input file")
+    let input: Vec<String> = read_input()
         .lines()
         .map(|line| line.to_string())
         .collect();
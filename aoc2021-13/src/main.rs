@@ -1,9 +1,9 @@
+use aoc_common::input::{read_input, split_input_by_blankline};
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs;
 
 fn main() {
-    let input = fs::read_to_string("input").expect("Couldn't read the input file");
+    let input = read_input();
 
     part_one(&input);
     part_two(&input);
@@ -152,13 +152,6 @@ impl Paper {
     }
 }
 
-fn split_input_by_blankline(input: &str) -> Vec<String> {
-    input
-        .split("\n\n")
-        .map(|string| string.to_string())
-        .collect()
-}
-
 fn get_pairs(input: &str) -> Vec<Pair> {
     input
         .split_whitespace()
@@ -1,12 +1,12 @@
+use aoc_common::input::{read_input, split_input_by_blankline};
 use std::collections::HashMap;
-use std::fs;
 
 type Polymer = String;
 type PolymerRules = HashMap<String, String>;
 type PolymerInventory = HashMap<String, u64>;
 
 fn main() {
-    let input = fs::read_to_string("input").expect("Couldn't read the string");
+    let input = read_input();
 
     let inputs = split_input_by_blankline(&input);
     let polymer = &inputs[0].to_string();
@@ -30,13 +30,6 @@ fn main() {
     dbg!(difference);
 }
 
-fn split_input_by_blankline(input: &str) -> Vec<String> {
-    input
-        .split("\n\n")
-        .map(|string| string.to_string())
-        .collect()
-}
-
 fn get_polymer_rules(input: &[String]) -> PolymerRules {
     let mut output: PolymerRules = HashMap::new();
     for line in input {
@@ -1,7 +1,7 @@
+use aoc_common::input::read_input;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::collections::HashMap;
-use std::fs;
 
 type Position = (i32, i32);
 type Cost = i32;
@@ -36,8 +36,7 @@ struct Edge {
 }
 
 fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("Couldn't read the input")
+    let input: Vec<String> = read_input()
         .lines()
         .map(|line| line.to_string())
         .collect();
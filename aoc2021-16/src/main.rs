@@ -1,15 +1,19 @@
-use std::fs;
+use aoc_common::checked::{self, Location, Overflow};
+use aoc_common::input::read_input;
+
+// The transmission is a single line of hexadecimal
+const TRANSMISSION: Location = Location::line(16, 1);
 
 fn main() {
-    let input = fs::read_to_string("input").expect("Couldn't read the input");
+    let input = read_input();
     let binary = hexadecimal_to_binary(&input);
 
     let packet = parse_packet(&binary);
-    let version_sum = sum_packet_versions(packet);
+    let version_sum = sum_packet_versions(packet).expect("couldn't sum the versions");
     dbg!(version_sum);
 
     let packet = parse_packet(&binary);
-    let output = evaluate_packet(packet);
+    let output = evaluate_packet(packet).expect("couldn't evaluate the packet");
     dbg!(output);
 }
 
@@ -122,20 +126,19 @@ fn parse_packet(input: &str) -> Packet {
     }
 }
 
-fn sum_packet_versions(packet: Packet) -> usize {
-    let mut output = 0;
-    output += packet.version;
+fn sum_packet_versions(packet: Packet) -> Result<usize, Overflow> {
+    let mut output = packet.version;
 
     if let Some(subpackets) = packet.subpackets {
         for subpacket in subpackets {
-            output += sum_packet_versions(subpacket);
+            output = TRANSMISSION.add(output, sum_packet_versions(subpacket)?)?;
         }
     }
 
-    output
+    Ok(output)
 }
 
-fn evaluate_packet(packet: Packet) -> usize {
+fn evaluate_packet(packet: Packet) -> Result<usize, checked::Error> {
     let mut output = 0;
     match packet.type_id {
         // addition
@@ -144,9 +147,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        output += binary_to_decimal(&content);
+                        output = TRANSMISSION.add(output, binary_to_decimal(&content)?)?;
                     } else {
-                        output += evaluate_packet(subpacket);
+                        output = TRANSMISSION.add(output, evaluate_packet(subpacket)?)?;
                     }
                 }
             }
@@ -159,9 +162,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        output *= binary_to_decimal(&content);
+                        output = TRANSMISSION.mul(output, binary_to_decimal(&content)?)?;
                     } else {
-                        output *= evaluate_packet(subpacket);
+                        output = TRANSMISSION.mul(output, evaluate_packet(subpacket)?)?;
                     }
                 }
             }
@@ -173,9 +176,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        values.push(binary_to_decimal(&content));
+                        values.push(binary_to_decimal(&content)?);
                     } else {
-                        values.push(evaluate_packet(subpacket));
+                        values.push(evaluate_packet(subpacket)?);
                     }
                 }
             }
@@ -188,9 +191,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        values.push(binary_to_decimal(&content));
+                        values.push(binary_to_decimal(&content)?);
                     } else {
-                        values.push(evaluate_packet(subpacket));
+                        values.push(evaluate_packet(subpacket)?);
                     }
                 }
             }
@@ -208,9 +211,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        values.push(binary_to_decimal(&content));
+                        values.push(binary_to_decimal(&content)?);
                     } else {
-                        values.push(evaluate_packet(subpacket));
+                        values.push(evaluate_packet(subpacket)?);
                     }
                 }
             }
@@ -225,9 +228,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        values.push(binary_to_decimal(&content));
+                        values.push(binary_to_decimal(&content)?);
                     } else {
-                        values.push(evaluate_packet(subpacket));
+                        values.push(evaluate_packet(subpacket)?);
                     }
                 }
             }
@@ -242,9 +245,9 @@ fn evaluate_packet(packet: Packet) -> usize {
             if let Some(subpackets) = packet.subpackets {
                 for subpacket in subpackets {
                     if let Some(content) = subpacket.content {
-                        values.push(binary_to_decimal(&content));
+                        values.push(binary_to_decimal(&content)?);
                     } else {
-                        values.push(evaluate_packet(subpacket));
+                        values.push(evaluate_packet(subpacket)?);
                     }
                 }
             }
@@ -254,7 +257,7 @@ fn evaluate_packet(packet: Packet) -> usize {
         }
         _ => unreachable!(),
     }
-    output
+    Ok(output)
 }
 
 fn hexadecimal_to_binary(hex: &str) -> String {
@@ -282,8 +285,8 @@ fn hexadecimal_to_binary(hex: &str) -> String {
     })
 }
 
-fn binary_to_decimal(binary: &str) -> usize {
-    usize::from_str_radix(binary, 2).unwrap()
+fn binary_to_decimal(binary: &str) -> Result<usize, checked::Error> {
+    TRANSMISSION.parse(binary, 2)
 }
 
 #[cfg(test)]
@@ -333,7 +336,7 @@ mod tests {
         let hex = "8A004A801A8002F478";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let version_sum = sum_packet_versions(packet);
+        let version_sum = sum_packet_versions(packet).unwrap();
         assert_eq!(version_sum, 16);
     }
 
@@ -342,7 +345,7 @@ mod tests {
         let hex = "620080001611562C8802118E34";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let version_sum = sum_packet_versions(packet);
+        let version_sum = sum_packet_versions(packet).unwrap();
         assert_eq!(version_sum, 12);
     }
 
@@ -351,7 +354,7 @@ mod tests {
         let hex = "C0015000016115A2E0802F182340";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let version_sum = sum_packet_versions(packet);
+        let version_sum = sum_packet_versions(packet).unwrap();
         assert_eq!(version_sum, 23);
     }
 
@@ -360,7 +363,7 @@ mod tests {
         let hex = "A0016C880162017C3686B18A3D4780";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let version_sum = sum_packet_versions(packet);
+        let version_sum = sum_packet_versions(packet).unwrap();
         assert_eq!(version_sum, 31);
     }
 
@@ -369,7 +372,7 @@ mod tests {
         let hex = "C200B40A82";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let sum = evaluate_packet(packet);
+        let sum = evaluate_packet(packet).unwrap();
         assert_eq!(sum, 3);
     }
 
@@ -378,7 +381,7 @@ mod tests {
         let hex = "04005AC33890";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let product = evaluate_packet(packet);
+        let product = evaluate_packet(packet).unwrap();
         assert_eq!(product, 54);
     }
 
@@ -387,7 +390,7 @@ mod tests {
         let hex = "880086C3E88112";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let product = evaluate_packet(packet);
+        let product = evaluate_packet(packet).unwrap();
         assert_eq!(product, 7);
     }
 
@@ -396,7 +399,7 @@ mod tests {
         let hex = "CE00C43D881120";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let product = evaluate_packet(packet);
+        let product = evaluate_packet(packet).unwrap();
         assert_eq!(product, 9);
     }
 
@@ -405,7 +408,7 @@ mod tests {
         let hex = "D8005AC2A8F0";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let lt = evaluate_packet(packet);
+        let lt = evaluate_packet(packet).unwrap();
         assert_eq!(lt, 1);
     }
 
@@ -414,7 +417,7 @@ mod tests {
         let hex = "F600BC2D8F";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let gt = evaluate_packet(packet);
+        let gt = evaluate_packet(packet).unwrap();
         assert_eq!(gt, 0);
     }
 
@@ -423,7 +426,7 @@ mod tests {
         let hex = "9C005AC2F8F0";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let et = evaluate_packet(packet);
+        let et = evaluate_packet(packet).unwrap();
         assert_eq!(et, 0);
     }
 
@@ -432,7 +435,22 @@ mod tests {
         let hex = "9C0141080250320F1802104A08";
         let binary = hexadecimal_to_binary(hex);
         let packet = parse_packet(&binary);
-        let et = evaluate_packet(packet);
+        let et = evaluate_packet(packet).unwrap();
         assert_eq!(et, 1);
     }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_an_oversized_literal() {
+        use aoc_common::checked::Operation;
+        let literal = "1".repeat(65);
+        assert_eq!(
+            binary_to_decimal(&literal),
+            Err(checked::Error::Overflow(Overflow {
+                day: 16,
+                operation: Operation::Parse,
+                line: Some(1),
+            }))
+        );
+    }
 }
@@ -1,22 +1,42 @@
+use aoc_common::checked::{Location, Overflow};
+use aoc_common::input::read_input;
 use std::ops::RangeInclusive;
 
+// The target area is the single line of input
+const TARGET_AREA: Location = Location::line(17, 1);
+
+type Goal = (RangeInclusive<i32>, RangeInclusive<i32>);
+
 fn main() {
-    // target area: x=281..311, y=-74..-54
-    let apex = find_highest_successful_arc_height((281..=311, -74..=-54));
+    let goal = parse_target_area(&read_input()).expect("couldn't read the target area");
+    let apex = find_highest_successful_arc_height(goal.clone()).expect("couldn't fire the probe");
     dbg!(apex);
-    let velocities_count = find_successful_velocities((281..=311, -74..=-54));
+    let velocities_count = find_successful_velocities(goal).expect("couldn't fire the probe");
     dbg!(velocities_count);
 }
 
+/// Reads `target area: x=281..311, y=-74..-54`.
+fn parse_target_area(input: &str) -> Option<Goal> {
+    let (x, y) = input
+        .trim()
+        .strip_prefix("target area: x=")?
+        .split_once(", y=")?;
+    let range = |range: &str| -> Option<RangeInclusive<i32>> {
+        let (start, end) = range.split_once("..")?;
+        Some(start.parse().ok()?..=end.parse().ok()?)
+    };
+    Some((range(x)?, range(y)?))
+}
+
 fn simulate_probe_arc(
     velocity: (i32, i32),
     goal: &(RangeInclusive<i32>, RangeInclusive<i32>),
-) -> Vec<(i32, i32)> {
+) -> Result<Vec<(i32, i32)>, Overflow> {
     let mut positions = vec![];
     let mut position = (0, 0);
     let mut velocity = velocity;
     loop {
-        let (p, v) = model_probe_arc(position, velocity);
+        let (p, v) = model_probe_arc(position, velocity)?;
         if check_probe_out_of_bounds(p, goal) {
             break;
         }
@@ -24,14 +44,19 @@ fn simulate_probe_arc(
         position = p;
         velocity = v;
     }
-    positions
+    Ok(positions)
 }
 
-fn model_probe_arc(position: (i32, i32), velocity: (i32, i32)) -> ((i32, i32), (i32, i32)) {
-    (
-        (position.0 + velocity.0, position.1 + velocity.1),
-        ((velocity.0 - 1).max(0), velocity.1 - 1),
-    )
+type ProbeState = ((i32, i32), (i32, i32));
+
+fn model_probe_arc(position: (i32, i32), velocity: (i32, i32)) -> Result<ProbeState, Overflow> {
+    Ok((
+        (
+            TARGET_AREA.add(position.0, velocity.0)?,
+            TARGET_AREA.add(position.1, velocity.1)?,
+        ),
+        ((velocity.0 - 1).max(0), TARGET_AREA.sub(velocity.1, 1)?),
+    ))
 }
 
 fn check_probe_was_in_goal(
@@ -66,12 +91,14 @@ fn get_apex_of_arc(arc: &[(i32, i32)]) -> i32 {
     highest
 }
 
-fn find_highest_successful_arc_height(goal: (RangeInclusive<i32>, RangeInclusive<i32>)) -> i32 {
+fn find_highest_successful_arc_height(
+    goal: (RangeInclusive<i32>, RangeInclusive<i32>),
+) -> Result<i32, Overflow> {
     // fuzz values between x 0 and 10 and y -10 and 10
     let mut heights = vec![];
     for y in 0..100 {
         for x in 0..1000 {
-            let arc = simulate_probe_arc((x, y), &goal);
+            let arc = simulate_probe_arc((x, y), &goal)?;
             if check_probe_was_in_goal(&arc, &goal) {
                 let apex = get_apex_of_arc(&arc);
                 heights.push(apex);
@@ -79,22 +106,24 @@ fn find_highest_successful_arc_height(goal: (RangeInclusive<i32>, RangeInclusive
         }
     }
 
-    *heights.iter().max().unwrap()
+    Ok(*heights.iter().max().unwrap())
 }
 
-fn find_successful_velocities(goal: (RangeInclusive<i32>, RangeInclusive<i32>)) -> i32 {
+fn find_successful_velocities(
+    goal: (RangeInclusive<i32>, RangeInclusive<i32>),
+) -> Result<i32, Overflow> {
     // fuzz values between x 0 and 10 and y -10 and 10
     let mut velocities = vec![];
     for y in -100..100 {
         for x in 0..1000 {
-            let arc = simulate_probe_arc((x, y), &goal);
+            let arc = simulate_probe_arc((x, y), &goal)?;
             if check_probe_was_in_goal(&arc, &goal) {
                 velocities.push((x, y));
             }
         }
     }
 
-    velocities.len() as i32
+    Ok(velocities.len() as i32)
 }
 
 #[cfg(test)]
@@ -104,32 +133,56 @@ mod tests {
     #[test]
     fn it_checks_if_a_probe_is_in_the_goal() {
         let goal = (20..=30, -10..=-5);
-        let arc = simulate_probe_arc((7, 2), &goal);
+        let arc = simulate_probe_arc((7, 2), &goal).unwrap();
         let result = check_probe_was_in_goal(&arc, &goal);
         assert_eq!(result, true);
 
-        let arc = simulate_probe_arc((6, 3), &goal);
+        let arc = simulate_probe_arc((6, 3), &goal).unwrap();
         let result = check_probe_was_in_goal(&arc, &goal);
         assert_eq!(result, true);
 
-        let arc = simulate_probe_arc((9, 0), &goal);
+        let arc = simulate_probe_arc((9, 0), &goal).unwrap();
         let result = check_probe_was_in_goal(&arc, &goal);
         assert_eq!(result, true);
 
-        let arc = simulate_probe_arc((17, -4), &goal);
+        let arc = simulate_probe_arc((17, -4), &goal).unwrap();
         let result = check_probe_was_in_goal(&arc, &goal);
         assert_eq!(result, false);
     }
 
+    #[test]
+    fn it_parses_the_target_area() {
+        assert_eq!(
+            parse_target_area("target area: x=20..30, y=-10..-5\n"),
+            Some((20..=30, -10..=-5))
+        );
+        assert_eq!(parse_target_area("target area: x=20..30"), None);
+    }
+
     #[test]
     fn it_finds_the_coolest_probe_shot() {
-        let apex = find_highest_successful_arc_height((20..=30, -10..=-5));
+        let apex = find_highest_successful_arc_height((20..=30, -10..=-5)).unwrap();
         assert_eq!(apex, 45);
     }
 
     #[test]
     fn it_finds_unique_good_velocities() {
-        let velocities = find_successful_velocities((20..=30, -10..=-5));
+        let velocities = find_successful_velocities((20..=30, -10..=-5)).unwrap();
         assert_eq!(velocities, 112);
     }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_a_probe_flying_off_the_map() {
+        use aoc_common::checked::Operation;
+        let output = model_probe_arc((i32::MAX, 0), (1, 0));
+        assert_eq!(
+            output,
+            Err(Overflow {
+                day: 17,
+                operation: Operation::Add,
+                line: Some(1),
+            })
+        );
+    }
 }
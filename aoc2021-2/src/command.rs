@@ -0,0 +1,187 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Forward(u16),
+    Up(u16),
+    Down(u16),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Forward(distance) => write!(f, "forward {}", distance),
+            Command::Up(distance) => write!(f, "up {}", distance),
+            Command::Down(distance) => write!(f, "down {}", distance),
+        }
+    }
+}
+
+/// A command and the input line it came from. Commands a script expands to share the
+/// line of whatever produced them, so a repeat's body keeps pointing at the body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    /// 1-based
+    pub number: usize,
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    Empty,
+    UnknownVerb(String),
+    MissingDistance(String),
+    NegativeDistance(String),
+    BadDistance(String),
+    ExtraTokens(String),
+    MissingCount,
+    BadCount(String),
+    MissingName,
+    ReservedName(String),
+    Redefined(String),
+    ExpectedBrace(String),
+    UnexpectedBrace(String),
+    Unclosed,
+    NothingToRepeat,
+    TooLong(usize),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Empty => write!(f, "expected a command"),
+            ErrorKind::UnknownVerb(verb) => write!(f, "unknown command {:?}", verb),
+            ErrorKind::MissingDistance(verb) => write!(f, "missing distance after {:?}", verb),
+            ErrorKind::NegativeDistance(distance) => {
+                write!(f, "distance {} is negative", distance)
+            }
+            ErrorKind::BadDistance(distance) => {
+                write!(f, "couldn't parse distance {:?}", distance)
+            }
+            ErrorKind::ExtraTokens(extra) => write!(f, "unexpected {:?} after the distance", extra),
+            ErrorKind::MissingCount => write!(f, "missing repeat count"),
+            ErrorKind::BadCount(count) => write!(f, "couldn't parse repeat count {:?}", count),
+            ErrorKind::MissingName => write!(f, "missing macro name"),
+            ErrorKind::ReservedName(name) => write!(f, "{:?} can't be a macro name", name),
+            ErrorKind::Redefined(name) => write!(f, "macro {:?} is already defined", name),
+            ErrorKind::ExpectedBrace(found) if found.is_empty() => {
+                write!(f, "expected \"{{\" before the end")
+            }
+            ErrorKind::ExpectedBrace(found) => write!(f, "expected \"{{\", found {:?}", found),
+            ErrorKind::UnexpectedBrace(brace) => write!(f, "unexpected {:?}", brace),
+            ErrorKind::Unclosed => write!(f, "\"{{\" is never closed"),
+            ErrorKind::NothingToRepeat => write!(f, "nothing to repeat yet"),
+            ErrorKind::TooLong(limit) => write!(f, "expands to more than {} commands", limit),
+        }
+    }
+}
+
+/// Where a course stopped making sense.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The 1-based input line
+    pub line: usize,
+    /// The 1-based character on the line
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl FromStr for Command {
+    type Err = ErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let verb = tokens.next().ok_or(ErrorKind::Empty)?;
+        let command: fn(u16) -> Command = match verb {
+            "forward" => Command::Forward,
+            "up" => Command::Up,
+            "down" => Command::Down,
+            _ => return Err(ErrorKind::UnknownVerb(verb.to_string())),
+        };
+
+        let distance = tokens
+            .next()
+            .ok_or_else(|| ErrorKind::MissingDistance(verb.to_string()))?;
+        let distance = match distance.parse::<u16>() {
+            Ok(distance) => distance,
+            Err(_) if distance.parse::<i64>().is_ok_and(|distance| distance < 0) => {
+                return Err(ErrorKind::NegativeDistance(distance.to_string()))
+            }
+            Err(_) => return Err(ErrorKind::BadDistance(distance.to_string())),
+        };
+
+        let extra: Vec<&str> = tokens.collect();
+        if !extra.is_empty() {
+            return Err(ErrorKind::ExtraTokens(extra.join(" ")));
+        }
+        Ok(command(distance))
+    }
+}
+
+/// Parses a whole course, reporting every bad line at once. Plain courses are one
+/// command per line, and [`crate::script`] covers everything else a course can use.
+pub fn parse_commands(input: &str) -> Result<Vec<Line>, Vec<ParseError>> {
+    crate::script::expand(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_commands() {
+        assert_eq!("forward 5".parse(), Ok(Command::Forward(5)));
+        assert_eq!("  up 3 ".parse(), Ok(Command::Up(3)));
+        assert_eq!("down 65535".parse(), Ok(Command::Down(65535)));
+        assert_eq!(Command::Down(8).to_string(), "down 8");
+    }
+
+    #[test]
+    fn it_rejects_bad_commands() {
+        assert_eq!("".parse::<Command>(), Err(ErrorKind::Empty));
+        assert_eq!(
+            "sideways 5".parse::<Command>(),
+            Err(ErrorKind::UnknownVerb("sideways".to_string()))
+        );
+        assert_eq!(
+            "up".parse::<Command>(),
+            Err(ErrorKind::MissingDistance("up".to_string()))
+        );
+        assert_eq!(
+            "down -4".parse::<Command>(),
+            Err(ErrorKind::NegativeDistance("-4".to_string()))
+        );
+        assert_eq!(
+            "down 70000".parse::<Command>(),
+            Err(ErrorKind::BadDistance("70000".to_string()))
+        );
+        assert_eq!(
+            "forward 5 metres fast".parse::<Command>(),
+            Err(ErrorKind::ExtraTokens("metres fast".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_reports_every_bad_line() {
+        let errors = parse_commands("forward 5\nback 2\ndown 5\nup -1").unwrap_err();
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 2, column 1: unknown command \"back\"",
+                "line 4, column 4: distance -1 is negative"
+            ]
+        );
+    }
+}
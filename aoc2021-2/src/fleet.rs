@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use aoc_common::checked::{Location, Overflow};
+
+use crate::command::Line;
+use crate::motion::MotionModel;
+
+/// One submarine's course and where it sets off from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Course<S> {
+    pub name: String,
+    pub commands: Vec<Line>,
+    pub start: S,
+}
+
+/// Two or more submarines coming together at the same `(horizontal, depth)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Encounter {
+    /// How many commands each submarine had run when they met, or 0 for their starting
+    /// positions
+    pub step: usize,
+    pub position: (i64, i64),
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for Encounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "step {}: {} meet at {:?}",
+            self.step,
+            self.names.join(" and "),
+            self.position
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub name: String,
+    pub commands: usize,
+    pub finish: (i64, i64),
+    pub max_depth: i64,
+    pub encounters: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} commands, finished at {:?}, deepest {}, {} encounters",
+            self.name, self.commands, self.finish, self.max_depth, self.encounters
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fleet {
+    pub summaries: Vec<Summary>,
+    pub encounters: Vec<Encounter>,
+}
+
+/// Steps every course together, one command each per step. A submarine whose course
+/// has ended stays where it stopped, so the others can still run into it. A group
+/// that stays together, whether sitting still or moving in step, only meets once,
+/// on the step it first comes together.
+pub fn simulate<M: MotionModel>(
+    model: &M,
+    courses: &[Course<M::State>],
+) -> Result<Fleet, Overflow> {
+    let mut states: Vec<M::State> = courses.iter().map(|course| course.start).collect();
+    let mut summaries: Vec<Summary> = courses
+        .iter()
+        .map(|course| {
+            let position = model.snapshot(&course.start);
+            Summary {
+                name: course.name.clone(),
+                commands: course.commands.len(),
+                finish: (position.horizontal, position.depth),
+                max_depth: position.depth,
+                encounters: 0,
+            }
+        })
+        .collect();
+    let mut encounters = vec![];
+    // The groups sharing a position on the previous step
+    let mut together: BTreeSet<Vec<usize>> = BTreeSet::new();
+    let length = courses
+        .iter()
+        .map(|course| course.commands.len())
+        .max()
+        .unwrap_or(0);
+
+    for step in 0..=length {
+        if step > 0 {
+            for (state, course) in states.iter_mut().zip(courses) {
+                if let Some(line) = course.commands.get(step - 1) {
+                    *state = model.step(*state, line.command, Location::line(2, line.number))?;
+                }
+            }
+        }
+
+        let mut occupied: BTreeMap<(i64, i64), Vec<usize>> = BTreeMap::new();
+        for (index, state) in states.iter().enumerate() {
+            let position = model.snapshot(state);
+            let summary = &mut summaries[index];
+            summary.finish = (position.horizontal, position.depth);
+            summary.max_depth = summary.max_depth.max(position.depth);
+            occupied.entry(summary.finish).or_default().push(index);
+        }
+
+        let mut groups = BTreeSet::new();
+        for (position, indexes) in occupied {
+            if indexes.len() < 2 {
+                continue;
+            }
+            if !together.contains(&indexes) {
+                for index in &indexes {
+                    summaries[*index].encounters += 1;
+                }
+                encounters.push(Encounter {
+                    step,
+                    position,
+                    names: indexes
+                        .iter()
+                        .map(|index| courses[*index].name.clone())
+                        .collect(),
+                });
+            }
+            groups.insert(indexes);
+        }
+        together = groups;
+    }
+
+    Ok(Fleet {
+        summaries,
+        encounters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::parse_commands;
+    use crate::motion::{PartTwo, SignedPartOne};
+
+    fn course<S>(name: &str, commands: &str, start: S) -> Course<S> {
+        Course {
+            name: name.to_string(),
+            commands: parse_commands(commands).unwrap(),
+            start,
+        }
+    }
+
+    #[test]
+    fn it_finds_crossing_courses() {
+        let courses = vec![
+            course("a", "forward 5\ndown 5\nforward 5", (0, 0)),
+            course("b", "up 3\nup 2\nforward 10", (5, 10)),
+        ];
+        let fleet = simulate(&SignedPartOne, &courses).unwrap();
+        // After two commands both are at (5, 5)
+        assert_eq!(
+            fleet.encounters,
+            vec![Encounter {
+                step: 2,
+                position: (5, 5),
+                names: vec!["a".to_string(), "b".to_string()],
+            }]
+        );
+        assert_eq!(
+            fleet.encounters[0].to_string(),
+            "step 2: a and b meet at (5, 5)"
+        );
+        assert_eq!(fleet.summaries[0].finish, (10, 5));
+        assert_eq!(fleet.summaries[1].finish, (15, 5));
+        assert_eq!(fleet.summaries[1].encounters, 1);
+    }
+
+    #[test]
+    fn it_waits_where_a_course_ends() {
+        let courses = vec![
+            course("short", "forward 3", (0, 0, 0)),
+            course(
+                "long",
+                "forward 1\nforward 1\nforward 1\nforward 1",
+                (0, 0, 0),
+            ),
+        ];
+        let fleet = simulate(&PartTwo, &courses).unwrap();
+        let steps: Vec<usize> = fleet
+            .encounters
+            .iter()
+            .map(|encounter| encounter.step)
+            .collect();
+        // Together at the start, then long catches up with short at step 3
+        assert_eq!(steps, vec![0, 3]);
+        assert_eq!(
+            fleet.summaries[0].to_string(),
+            "short: 1 commands, finished at (3, 0), deepest 0, 2 encounters"
+        );
+    }
+
+    #[test]
+    fn it_meets_once_while_they_stay_together() {
+        let courses = vec![
+            course("a", "forward 2\ndown 1\ndown 1\ndown 1", (0, 0, 0)),
+            course("b", "forward 2", (0, 0, 0)),
+        ];
+        let fleet = simulate(&PartTwo, &courses).unwrap();
+        // Together from the start, then at (2, 0) while a only turns and b has stopped
+        assert_eq!(
+            fleet.encounters,
+            vec![Encounter {
+                step: 0,
+                position: (0, 0),
+                names: vec!["a".to_string(), "b".to_string()],
+            }]
+        );
+        assert_eq!(fleet.summaries[0].encounters, 1);
+        assert_eq!(fleet.summaries[1].encounters, 1);
+    }
+
+    #[test]
+    fn it_follows_each_rule() {
+        let commands = "down 2\nforward 3";
+        let one = simulate(&SignedPartOne, &[course("a", commands, (0, 0))]).unwrap();
+        let two = simulate(&PartTwo, &[course("a", commands, (0, 0, 0))]).unwrap();
+        assert_eq!(one.summaries[0].finish, (3, 2));
+        assert_eq!(two.summaries[0].finish, (3, 6));
+        assert!(one.encounters.is_empty());
+    }
+}
@@ -1,108 +1,240 @@
+mod command;
+mod fleet;
+mod motion;
+mod planner;
+mod script;
+mod trace;
+
+use aoc_common::checked::{Location, Overflow};
+use aoc_common::input::read_input;
+use command::{parse_commands, Line};
+use fleet::Course;
+use motion::{drive, Choice, PartOne, PartTwo, SignedPartOne};
+use std::env;
 use std::fs;
+use std::process;
 
-fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("couldn't read the file")
-        .lines()
-        .map(|line| line.parse().expect("couldn't parse line"))
-        .collect();
+const USAGE: &str = r#"usage:
+    aoc2021-2
+    aoc2021-2 --model MODEL
+    aoc2021-2 --trace MODEL [--format csv|json]
+    aoc2021-2 --plan HORIZONTAL DEPTH
+    aoc2021-2 --fleet (part-one | part-two) COURSE[@HORIZONTAL,DEPTH]...
 
-    let start = (0, 0);
-    let (x, y) = p1_execute_movements(input.clone(), start);
-    dbg!(x, y);
-    dbg!(x as u32 * y as u32);
+models: part-one, part-two, clamped:FLOOR, fuelled:FUEL, heading"#;
 
-    let start = (0, 0, 0);
-    let (x, y, _a) = p2_execute_movements(input, start);
-    dbg!(x, y);
-    dbg!(x as u32 * y as u32);
-}
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [flag, rule, courses @ ..] = args.as_slice() {
+        if flag == "--fleet" && !courses.is_empty() {
+            run_fleet(rule, courses);
+            return;
+        }
+    }
+    if let [flag, horizontal, depth] = args.as_slice() {
+        if flag == "--plan" {
+            match (horizontal.parse(), depth.parse()) {
+                (Ok(horizontal), Ok(depth)) => print_plan((horizontal, depth)),
+                _ => usage(),
+            }
+            return;
+        }
+    }
 
-fn parse_movement(string: String) -> (String, u16) {
-    let mut iter = string.split_whitespace();
-    let dir = iter.next().unwrap();
-    let dist = iter.next().unwrap();
-    let direction = dir.to_string();
-    let distance = dist
-        .parse::<u16>()
-        .expect("couldn't parse string as integer");
+    let input = read_input();
+    let commands = parse_commands(&input).unwrap_or_else(|errors| {
+        for error in errors {
+            eprintln!("{}", error);
+        }
+        process::exit(1);
+    });
 
-    (direction, distance)
+    match args.as_slice() {
+        [] => parts(&commands),
+        [flag, model] if flag == "--model" => {
+            let state = parse_model(model)
+                .run(&commands)
+                .expect("couldn't follow the course");
+            println!("{}", state);
+        }
+        [flag, model] if flag == "--trace" => export_trace(&commands, parse_model(model), "csv"),
+        [flag, model, format_flag, format] if flag == "--trace" && format_flag == "--format" => {
+            export_trace(&commands, parse_model(model), format)
+        }
+        _ => usage(),
+    }
 }
 
-fn p1_execute_movement(movement: String, position: (u16, u16)) -> (u16, u16) {
-    let (direction, distance) = parse_movement(movement);
-    match direction.as_str() {
-        "forward" => (position.0 + distance, position.1),
-        "up" => (position.0, position.1 - distance),
-        "down" => (position.0, position.1 + distance),
-        _ => position,
+/// Prints the plan as a course file, so it can be fed straight back in.
+fn print_plan(target: (i32, i32)) {
+    match planner::plan(target) {
+        Ok(commands) => {
+            for command in &commands {
+                println!("{}", command);
+            }
+            eprintln!("{} commands", commands.len());
+        }
+        Err(error) => {
+            eprintln!("can't plan a course to {:?}: {}", target, error);
+            process::exit(1);
+        }
     }
 }
 
-fn p1_execute_movements(movements: Vec<String>, position: (u16, u16)) -> (u16, u16) {
-    let mut pos = position;
-    for movement in movements {
-        pos = p1_execute_movement(movement, pos);
+/// Reads `PATH` or `PATH@HORIZONTAL,DEPTH`, starting at the surface by default.
+fn parse_course(arg: &str) -> Option<(String, (i32, i32))> {
+    match arg.rsplit_once('@') {
+        Some((path, start)) => {
+            let (horizontal, depth) = start.split_once(',')?;
+            Some((
+                path.to_string(),
+                (horizontal.parse().ok()?, depth.parse().ok()?),
+            ))
+        }
+        None => Some((arg.to_string(), (0, 0))),
     }
-    pos
 }
 
-fn p2_execute_movement(movement: String, position: (i32, i32, i32)) -> (i32, i32, i32) {
-    let (direction, distance) = parse_movement(movement);
-    match direction.as_str() {
-        "forward" => (
-            position.0 + distance as i32,
-            position.1 + distance as i32 * position.2,
-            position.2,
-        ),
-        "up" => (position.0, position.1, position.2 - distance as i32),
-        "down" => (position.0, position.1, position.2 + distance as i32),
-        _ => position,
+fn read_course(path: &str) -> Vec<Line> {
+    let input = fs::read_to_string(path).unwrap_or_else(|_| panic!("couldn't read {}", path));
+    parse_commands(&input).unwrap_or_else(|errors| {
+        for error in errors {
+            eprintln!("{}: {}", path, error);
+        }
+        process::exit(1);
+    })
+}
+
+/// Runs every course at once and prints when submarines meet, then how each one did.
+fn run_fleet(rule: &str, args: &[String]) {
+    let courses: Vec<(String, Vec<Line>, (i32, i32))> = args
+        .iter()
+        .map(|arg| {
+            let (path, start) = parse_course(arg).unwrap_or_else(|| usage());
+            let commands = read_course(&path);
+            (path, commands, start)
+        })
+        .collect();
+
+    let fleet = match rule {
+        "part-one" => {
+            let courses: Vec<Course<(i64, i64)>> = courses
+                .into_iter()
+                .map(|(name, commands, start)| Course {
+                    name,
+                    commands,
+                    start: (start.0 as i64, start.1 as i64),
+                })
+                .collect();
+            fleet::simulate(&SignedPartOne, &courses)
+        }
+        "part-two" => {
+            let courses: Vec<Course<(i32, i32, i32)>> = courses
+                .into_iter()
+                .map(|(name, commands, start)| Course {
+                    name,
+                    commands,
+                    start: (start.0, start.1, 0),
+                })
+                .collect();
+            fleet::simulate(&PartTwo, &courses)
+        }
+        _ => usage(),
     }
+    .expect("couldn't follow the courses");
+
+    for encounter in &fleet.encounters {
+        println!("{}", encounter);
+    }
+    for summary in &fleet.summaries {
+        println!("{}", summary);
+    }
+}
+
+fn parse_model(model: &str) -> Choice {
+    model.parse().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        usage();
+    })
 }
 
-fn p2_execute_movements(movements: Vec<String>, position: (i32, i32, i32)) -> (i32, i32, i32) {
-    let mut pos = position;
-    for movement in movements {
-        pos = p2_execute_movement(movement, pos);
+/// Prints the trace as CSV or JSON. A CSV trace's summary goes to stderr, so stdout is
+/// just the table.
+fn export_trace(commands: &[Line], model: Choice, format: &str) {
+    let trace = model.trace(commands).expect("couldn't follow the course");
+    match format {
+        "csv" => {
+            print!("{}", trace.to_csv());
+            let summary = trace.summary();
+            eprintln!(
+                "max depth {} at step {}",
+                summary.max_depth, summary.max_depth_step
+            );
+            if !summary.above_surface.is_empty() {
+                eprintln!("above the surface at steps {:?}", summary.above_surface);
+            }
+        }
+        "json" => print!("{}", trace.to_json()),
+        _ => usage(),
     }
-    pos
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn parts(commands: &[Line]) {
+    let start = (0, 0);
+    let (x, y) = p1_execute_movements(commands, start).expect("couldn't follow the course");
+    dbg!(x, y);
+    let product = Location::new(2)
+        .mul(i64::from(x), i64::from(y))
+        .expect("couldn't multiply the position");
+    dbg!(product);
+
+    let start = (0, 0, 0);
+    let (x, y, _a) = p2_execute_movements(commands, start).expect("couldn't follow the course");
+    dbg!(x, y);
+    let product = Location::new(2)
+        .mul(i64::from(x), i64::from(y))
+        .expect("couldn't multiply the position");
+    dbg!(product);
+}
+
+fn p1_execute_movements(commands: &[Line], position: (u16, u16)) -> Result<(u16, u16), Overflow> {
+    drive(&PartOne, commands, position)
+}
+
+fn p2_execute_movements(
+    commands: &[Line],
+    position: (i32, i32, i32),
+) -> Result<(i32, i32, i32), Overflow> {
+    drive(&PartTwo, commands, position)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use command::Command;
+    use motion::MotionModel;
 
-    #[test]
-    fn parse_line_works() {
-        let input = "forward 5".to_string();
-        let (direction, distance) = parse_movement(input);
-        assert_eq!(direction, "forward");
-        assert_eq!(distance, 5);
-    }
+    const INPUT: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
 
     #[test]
     fn p1_execute_movement_works() {
-        let movement = "forward 5".to_string();
+        let movement = Command::Forward(5);
         let start = (0, 0);
-        let (x, y) = p1_execute_movement(movement, start);
+        let (x, y) = PartOne.step(start, movement, Location::line(2, 1)).unwrap();
         assert_eq!(x, 5);
         assert_eq!(y, 0);
     }
 
     #[test]
     fn p1_execute_movements_works() {
-        let input = vec![
-            "forward 5".to_string(),
-            "down 5".to_string(),
-            "forward 8".to_string(),
-            "up 3".to_string(),
-            "down 8".to_string(),
-            "forward 2".to_string(),
-        ];
+        let input = parse_commands(INPUT).unwrap();
         let start = (0, 0);
-        let (x, y) = p1_execute_movements(input, start);
+        let (x, y) = p1_execute_movements(&input, start).unwrap();
         assert_eq!(x, 15);
         assert_eq!(y, 10);
         assert_eq!(x * y, 150)
@@ -110,9 +242,9 @@ mod tests {
 
     #[test]
     fn p2_execute_movement_works() {
-        let movement = "forward 5".to_string();
+        let movement = Command::Forward(5);
         let start = (0, 0, 0);
-        let (x, y, a) = p2_execute_movement(movement, start);
+        let (x, y, a) = PartTwo.step(start, movement, Location::line(2, 1)).unwrap();
         assert_eq!(x, 5);
         assert_eq!(y, 0);
         assert_eq!(a, 0);
@@ -120,18 +252,37 @@ mod tests {
 
     #[test]
     fn p2_execute_movements_works() {
-        let input = vec![
-            "forward 5".to_string(),
-            "down 5".to_string(),
-            "forward 8".to_string(),
-            "up 3".to_string(),
-            "down 8".to_string(),
-            "forward 2".to_string(),
-        ];
+        let input = parse_commands(INPUT).unwrap();
         let start = (0, 0, 0);
-        let (x, y, _a) = p2_execute_movements(input, start);
+        let (x, y, _a) = p2_execute_movements(&input, start).unwrap();
         assert_eq!(x, 15);
         assert_eq!(y, 60);
         assert_eq!(x * y, 900);
     }
+
+    #[test]
+    fn it_parses_fleet_courses() {
+        assert_eq!(parse_course("a.txt"), Some(("a.txt".to_string(), (0, 0))));
+        assert_eq!(
+            parse_course("courses/b@10,-5"),
+            Some(("courses/b".to_string(), (10, -5)))
+        );
+        assert_eq!(parse_course("b@10"), None);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn p1_execute_movements_reports_overflow() {
+        use aoc_common::checked::Operation;
+        let input = parse_commands("forward 5\ndown 2\nup 3").unwrap();
+        let output = p1_execute_movements(&input, (0, 0));
+        assert_eq!(
+            output,
+            Err(Overflow {
+                day: 2,
+                operation: Operation::Sub,
+                line: Some(3),
+            })
+        );
+    }
 }
@@ -0,0 +1,458 @@
+use std::fmt;
+use std::str::FromStr;
+
+use aoc_common::checked::{Location, Overflow};
+
+use crate::command::{Command, Line};
+use crate::trace::{trace, Trace};
+
+/// Where a model's state puts the submarine, in a form every model shares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snapshot {
+    pub horizontal: i64,
+    pub depth: i64,
+    /// Only for models that steer by aim
+    pub aim: Option<i64>,
+}
+
+/// How a command moves the submarine. Every model runs through the same [`drive`].
+pub trait MotionModel {
+    type State: Copy + fmt::Debug;
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow>;
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot;
+}
+
+/// Runs a whole course through a model, reporting overflow against the command's line.
+pub fn drive<M: MotionModel>(
+    model: &M,
+    commands: &[Line],
+    start: M::State,
+) -> Result<M::State, Overflow> {
+    commands.iter().try_fold(start, |state, line| {
+        model.step(state, line.command, Location::line(2, line.number))
+    })
+}
+
+/// Up and down change the depth directly. The state is `(horizontal, depth)`.
+pub struct PartOne;
+
+impl MotionModel for PartOne {
+    type State = (u16, u16);
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow> {
+        let (horizontal, depth) = state;
+        Ok(match command {
+            Command::Forward(distance) => (at.add(horizontal, distance)?, depth),
+            Command::Up(distance) => (horizontal, at.sub(depth, distance)?),
+            Command::Down(distance) => (horizontal, at.add(depth, distance)?),
+        })
+    }
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        Snapshot {
+            horizontal: state.0 as i64,
+            depth: state.1 as i64,
+            aim: None,
+        }
+    }
+}
+
+/// Part one over signed depths, so a course that rises above the surface shows up as
+/// a negative depth instead of overflowing.
+pub struct SignedPartOne;
+
+impl MotionModel for SignedPartOne {
+    type State = (i64, i64);
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow> {
+        let (horizontal, depth) = state;
+        Ok(match command {
+            Command::Forward(distance) => (at.add(horizontal, distance as i64)?, depth),
+            Command::Up(distance) => (horizontal, at.sub(depth, distance as i64)?),
+            Command::Down(distance) => (horizontal, at.add(depth, distance as i64)?),
+        })
+    }
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        Snapshot {
+            horizontal: state.0,
+            depth: state.1,
+            aim: None,
+        }
+    }
+}
+
+fn aimed_snapshot(state: &(i32, i32, i32)) -> Snapshot {
+    Snapshot {
+        horizontal: state.0 as i64,
+        depth: state.1 as i64,
+        aim: Some(state.2 as i64),
+    }
+}
+
+/// Up and down change the aim, and moving forward dives along it. The state is
+/// `(horizontal, depth, aim)`.
+pub struct PartTwo;
+
+impl MotionModel for PartTwo {
+    type State = (i32, i32, i32);
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow> {
+        let (horizontal, depth, aim) = state;
+        Ok(match command {
+            Command::Forward(distance) => {
+                let distance = distance as i32;
+                (
+                    at.add(horizontal, distance)?,
+                    at.add(depth, at.mul(distance, aim)?)?,
+                    aim,
+                )
+            }
+            Command::Up(distance) => (horizontal, depth, at.sub(aim, distance as i32)?),
+            Command::Down(distance) => (horizontal, depth, at.add(aim, distance as i32)?),
+        })
+    }
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        aimed_snapshot(state)
+    }
+}
+
+/// Part two, but the submarine can't rise above the surface or sink below `floor`.
+pub struct Clamped {
+    /// Can't be negative, since the surface is at 0
+    pub floor: i32,
+}
+
+impl MotionModel for Clamped {
+    type State = (i32, i32, i32);
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow> {
+        let (horizontal, depth, aim) = PartTwo.step(state, command, at)?;
+        Ok((horizontal, depth.clamp(0, self.floor), aim))
+    }
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        aimed_snapshot(state)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuelState {
+    pub horizontal: u32,
+    pub depth: u32,
+    pub fuel: u32,
+}
+
+/// Part one, burning a unit of fuel per unit travelled. A command the tank can't cover
+/// goes as far as the fuel does, and a dry submarine stays put. Rising stops at the
+/// surface, and only burns fuel for the distance actually risen.
+pub struct Fuelled;
+
+impl MotionModel for Fuelled {
+    type State = FuelState;
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        at: Location,
+    ) -> Result<Self::State, Overflow> {
+        let (Command::Forward(distance) | Command::Up(distance) | Command::Down(distance)) =
+            command;
+        let mut distance = (distance as u32).min(state.fuel);
+        if let Command::Up(_) = command {
+            distance = distance.min(state.depth);
+        }
+        let fuel = state.fuel - distance;
+        Ok(match command {
+            Command::Forward(_) => FuelState {
+                horizontal: at.add(state.horizontal, distance)?,
+                fuel,
+                ..state
+            },
+            Command::Up(_) => FuelState {
+                depth: at.sub(state.depth, distance)?,
+                fuel,
+                ..state
+            },
+            Command::Down(_) => FuelState {
+                depth: at.add(state.depth, distance)?,
+                fuel,
+                ..state
+            },
+        })
+    }
+
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        Snapshot {
+            horizontal: state.horizontal as i64,
+            depth: state.depth as i64,
+            aim: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadingState {
+    pub horizontal: f64,
+    pub depth: f64,
+    /// Degrees below the horizontal, from -90 (straight up) to 90 (straight down)
+    pub pitch: i32,
+}
+
+/// Up and down turn the submarine's nose by that many degrees, and moving forward
+/// travels along the heading.
+pub struct Heading;
+
+impl MotionModel for Heading {
+    type State = HeadingState;
+
+    fn step(
+        &self,
+        state: Self::State,
+        command: Command,
+        _at: Location,
+    ) -> Result<Self::State, Overflow> {
+        Ok(match command {
+            Command::Forward(distance) => {
+                let radians = (state.pitch as f64).to_radians();
+                HeadingState {
+                    horizontal: state.horizontal + distance as f64 * radians.cos(),
+                    depth: state.depth + distance as f64 * radians.sin(),
+                    ..state
+                }
+            }
+            Command::Up(degrees) => HeadingState {
+                pitch: (state.pitch - degrees as i32).max(-90),
+                ..state
+            },
+            Command::Down(degrees) => HeadingState {
+                pitch: (state.pitch + degrees as i32).min(90),
+                ..state
+            },
+        })
+    }
+
+    /// Rounded to the nearest whole unit
+    fn snapshot(&self, state: &Self::State) -> Snapshot {
+        Snapshot {
+            horizontal: state.horizontal.round() as i64,
+            depth: state.depth.round() as i64,
+            aim: None,
+        }
+    }
+}
+
+fn full_tank(fuel: u32) -> FuelState {
+    FuelState {
+        horizontal: 0,
+        depth: 0,
+        fuel,
+    }
+}
+
+const LEVEL: HeadingState = HeadingState {
+    horizontal: 0.0,
+    depth: 0.0,
+    pitch: 0,
+};
+
+/// A model picked on the command line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Choice {
+    PartOne,
+    PartTwo,
+    Clamped { floor: i32 },
+    Fuelled { fuel: u32 },
+    Heading,
+}
+
+impl FromStr for Choice {
+    type Err = String;
+
+    /// `part-one`, `part-two`, `clamped:FLOOR`, `fuelled:FUEL` or `heading`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, parameter) = match s.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (s, None),
+        };
+        let bad = || format!("unknown motion model {:?}", s);
+        match (name, parameter) {
+            ("part-one", None) => Ok(Choice::PartOne),
+            ("part-two", None) => Ok(Choice::PartTwo),
+            ("clamped", Some(floor)) => match floor.parse().map_err(|_| bad())? {
+                floor if floor < 0 => Err(format!(
+                    "the floor can't be above the surface, at {}",
+                    floor
+                )),
+                floor => Ok(Choice::Clamped { floor }),
+            },
+            ("fuelled", Some(fuel)) => Ok(Choice::Fuelled {
+                fuel: fuel.parse().map_err(|_| bad())?,
+            }),
+            ("heading", None) => Ok(Choice::Heading),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl Choice {
+    /// Drives the course from the surface and describes where it ends up.
+    pub fn run(&self, commands: &[Line]) -> Result<String, Overflow> {
+        Ok(match *self {
+            Choice::PartOne => format!("{:?}", drive(&PartOne, commands, (0, 0))?),
+            Choice::PartTwo => format!("{:?}", drive(&PartTwo, commands, (0, 0, 0))?),
+            Choice::Clamped { floor } => {
+                format!("{:?}", drive(&Clamped { floor }, commands, (0, 0, 0))?)
+            }
+            Choice::Fuelled { fuel } => {
+                format!("{:?}", drive(&Fuelled, commands, full_tank(fuel))?)
+            }
+            Choice::Heading => format!("{:?}", drive(&Heading, commands, LEVEL)?),
+        })
+    }
+
+    /// Traces the course from the surface. Part one is traced over signed depths, so
+    /// rising above the surface shows in the trace instead of overflowing.
+    pub fn trace(&self, commands: &[Line]) -> Result<Trace, Overflow> {
+        match *self {
+            Choice::PartOne => trace(&SignedPartOne, commands, (0, 0)),
+            Choice::PartTwo => trace(&PartTwo, commands, (0, 0, 0)),
+            Choice::Clamped { floor } => trace(&Clamped { floor }, commands, (0, 0, 0)),
+            Choice::Fuelled { fuel } => trace(&Fuelled, commands, full_tank(fuel)),
+            Choice::Heading => trace(&Heading, commands, LEVEL),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::parse_commands;
+
+    const INPUT: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
+    #[test]
+    fn it_clamps_the_depth() {
+        let commands = parse_commands(INPUT).unwrap();
+        // Part two reaches 60, but the floor is at 50
+        let state = drive(&Clamped { floor: 50 }, &commands, (0, 0, 0)).unwrap();
+        assert_eq!(state, (15, 50, 10));
+
+        let commands = parse_commands("up 5\nforward 3").unwrap();
+        let state = drive(&Clamped { floor: 50 }, &commands, (0, 0, 0)).unwrap();
+        assert_eq!(state, (3, 0, -5));
+    }
+
+    #[test]
+    fn it_runs_out_of_fuel() {
+        let commands = parse_commands(INPUT).unwrap();
+        let start = FuelState {
+            horizontal: 0,
+            depth: 0,
+            fuel: 20,
+        };
+        // 5 + 5 + 8 leaves 2 of the 3 it takes to rise, then nothing
+        assert_eq!(
+            drive(&Fuelled, &commands, start).unwrap(),
+            FuelState {
+                horizontal: 13,
+                depth: 3,
+                fuel: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn it_stops_rising_at_the_surface() {
+        let commands = parse_commands("down 2\nup 5\nforward 1").unwrap();
+        // Rising 5 from 2 only takes the submarine, and the fuel, 2 units
+        assert_eq!(
+            drive(&Fuelled, &commands, full_tank(20)).unwrap(),
+            FuelState {
+                horizontal: 1,
+                depth: 0,
+                fuel: 15,
+            }
+        );
+    }
+
+    #[test]
+    fn it_follows_the_heading() {
+        let commands = parse_commands("down 90\nforward 10\nup 90\nforward 5\nup 200").unwrap();
+        let start = HeadingState {
+            horizontal: 0.0,
+            depth: 0.0,
+            pitch: 0,
+        };
+        let state = drive(&Heading, &commands, start).unwrap();
+        assert!((state.horizontal - 5.0).abs() < 1e-9);
+        assert!((state.depth - 10.0).abs() < 1e-9);
+        assert_eq!(state.pitch, -90);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflow_on_the_source_line() {
+        use aoc_common::checked::Operation;
+        let commands = parse_commands("down 2\nrepeat 3 {\n  up 1\n}").unwrap();
+        // The third up is the fourth command, but it comes from line 3
+        assert_eq!(
+            drive(&PartOne, &commands, (0, 0)),
+            Err(Overflow {
+                day: 2,
+                operation: Operation::Sub,
+                line: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn it_parses_choices() {
+        assert_eq!("part-two".parse(), Ok(Choice::PartTwo));
+        assert_eq!("clamped:50".parse(), Ok(Choice::Clamped { floor: 50 }));
+        assert_eq!("fuelled:100".parse(), Ok(Choice::Fuelled { fuel: 100 }));
+        assert!("clamped".parse::<Choice>().is_err());
+        assert_eq!(
+            "clamped:-5".parse::<Choice>(),
+            Err("the floor can't be above the surface, at -5".to_string())
+        );
+        assert!("heading:3".parse::<Choice>().is_err());
+        assert!("teleport".parse::<Choice>().is_err());
+    }
+
+    #[test]
+    fn it_describes_the_final_state() {
+        let commands = parse_commands(INPUT).unwrap();
+        assert_eq!(Choice::PartOne.run(&commands).unwrap(), "(15, 10)");
+        assert_eq!(Choice::PartTwo.run(&commands).unwrap(), "(15, 60, 10)");
+    }
+}
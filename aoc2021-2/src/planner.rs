@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::command::{Command, Line};
+
+/// The furthest one command can move the submarine or turn its aim.
+const REACH: i64 = u16::MAX as i64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanError {
+    /// No course gets there: the submarine can't go backwards
+    Impossible,
+    /// Depth only changes while moving forward, so a depth with no horizontal
+    /// distance to dive over is out of reach
+    OutOfRange,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::Impossible => write!(f, "no course reaches that position"),
+            PlanError::OutOfRange => write!(f, "a depth needs some distance to dive over"),
+        }
+    }
+}
+
+/// A move of any size, before it's split into commands that fit in a `u16`.
+#[derive(Debug, Clone, Copy)]
+enum Leg {
+    Forward(i64),
+    Aim(i64),
+}
+
+impl Leg {
+    /// How many commands it takes, with no command at all for a zero-sized leg.
+    fn len(self) -> i64 {
+        let (Leg::Forward(size) | Leg::Aim(size)) = self;
+        (size.abs() + REACH - 1) / REACH
+    }
+
+    fn commands(self) -> impl Iterator<Item = Command> {
+        let (Leg::Forward(size) | Leg::Aim(size)) = self;
+        let chunk = move |index: i64| (size.abs() - index * REACH).min(REACH) * size.signum();
+        (0..self.len()).map(move |index| match self {
+            Leg::Forward(_) => forward(chunk(index)),
+            Leg::Aim(_) => aim(chunk(index)),
+        })
+    }
+}
+
+/// The shortest course that ends exactly at `(horizontal, depth)` under part two's aim
+/// rules, starting from the surface.
+///
+/// Depth only changes when moving forward, so any dive needs an aim and a forward
+/// leg, and one of each works when the horizontal distance divides the depth.
+/// Splitting the depth into `q * horizontal + r` always works by diving at `q` and
+/// then at `q + 1` for the last `r`, and the submarine can also travel level and then
+/// dive for any stretch that divides the depth. Legs longer than one command can
+/// carry are split over several, and whichever course needs fewest commands wins.
+pub fn plan(target: (i32, i32)) -> Result<Vec<Command>, PlanError> {
+    let (horizontal, depth) = (target.0 as i64, target.1 as i64);
+    if horizontal < 0 {
+        return Err(PlanError::Impossible);
+    }
+    if horizontal == 0 {
+        return if depth == 0 {
+            Ok(vec![])
+        } else {
+            Err(PlanError::OutOfRange)
+        };
+    }
+
+    let (q, r) = (depth.div_euclid(horizontal), depth.rem_euclid(horizontal));
+    let mut courses = vec![if r == 0 {
+        vec![Leg::Aim(q), Leg::Forward(horizontal)]
+    } else {
+        vec![
+            Leg::Aim(q),
+            Leg::Forward(horizontal - r),
+            Leg::Aim(1),
+            Leg::Forward(r),
+        ]
+    }];
+    if depth != 0 {
+        courses.extend(
+            divisors(depth.abs())
+                .filter(|&stretch| stretch < horizontal)
+                .map(|stretch| {
+                    vec![
+                        Leg::Forward(horizontal - stretch),
+                        Leg::Aim(depth / stretch),
+                        Leg::Forward(stretch),
+                    ]
+                }),
+        );
+    }
+    let commands: Vec<Command> = courses
+        .into_iter()
+        .min_by_key(|legs| legs.iter().map(|leg| leg.len()).sum::<i64>())
+        .expect("there's always the split dive")
+        .into_iter()
+        .flat_map(Leg::commands)
+        .collect();
+
+    // Numbered the way they'll be printed, one per line
+    let course: Vec<Line> = commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| Line {
+            number: index + 1,
+            command: *command,
+        })
+        .collect();
+    let (reached_horizontal, reached_depth, _) =
+        crate::p2_execute_movements(&course, (0, 0, 0)).expect("couldn't follow the plan");
+    assert_eq!(
+        (reached_horizontal, reached_depth),
+        target,
+        "the plan {:?} misses the target",
+        commands
+    );
+    Ok(commands)
+}
+
+/// Every divisor of `n`, largest first.
+fn divisors(n: i64) -> impl Iterator<Item = i64> {
+    let small: Vec<i64> = (1..)
+        .take_while(|i| i * i <= n)
+        .filter(|i| n % i == 0)
+        .collect();
+    let large: Vec<i64> = small
+        .iter()
+        .map(|i| n / i)
+        .filter(|&large| large * large != n)
+        .collect();
+    large.into_iter().chain(small.into_iter().rev())
+}
+
+fn forward(distance: i64) -> Command {
+    Command::Forward(distance as u16)
+}
+
+fn aim(change: i64) -> Command {
+    if change < 0 {
+        Command::Up(-change as u16)
+    } else {
+        Command::Down(change as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_dives_once_when_it_can() {
+        assert_eq!(
+            plan((15, 60)),
+            Ok(vec![Command::Down(4), Command::Forward(15)])
+        );
+        assert_eq!(
+            plan((15, -30)),
+            Ok(vec![Command::Up(2), Command::Forward(15)])
+        );
+        assert_eq!(plan((7, 0)), Ok(vec![Command::Forward(7)]));
+        assert_eq!(plan((0, 0)), Ok(vec![]));
+    }
+
+    #[test]
+    fn it_travels_level_before_diving() {
+        // 7 doesn't divide by 15, so go 8 level and dive the last 7 at an aim of 1
+        assert_eq!(
+            plan((15, 7)),
+            Ok(vec![
+                Command::Forward(8),
+                Command::Down(1),
+                Command::Forward(7)
+            ])
+        );
+    }
+
+    #[test]
+    fn it_splits_deep_dives() {
+        // 131069 is odd and too steep to dive in one unit, so 65534 + 65535
+        assert_eq!(
+            plan((2, 131069)),
+            Ok(vec![
+                Command::Down(65534),
+                Command::Forward(1),
+                Command::Down(1),
+                Command::Forward(1),
+            ])
+        );
+    }
+
+    #[test]
+    fn nothing_shorter_reaches_small_targets() {
+        use std::collections::{HashMap, HashSet};
+
+        // Every (horizontal, depth) reachable in up to 4 commands of at most 20, keeping
+        // only what could still end up at a horizontal position of 6 or less
+        let mut best: HashMap<(i32, i32), usize> = HashMap::new();
+        let mut seen = HashSet::from([(0, 0, 0)]);
+        let mut frontier = vec![(0, 0, 0)];
+        for cost in 0..=4 {
+            let mut next = vec![];
+            for (horizontal, depth, aim) in frontier {
+                best.entry((horizontal, depth)).or_insert(cost);
+                if cost == 4 {
+                    continue;
+                }
+                for distance in 1..=20 {
+                    for state in [
+                        (horizontal + distance, depth + aim * distance, aim),
+                        (horizontal, depth, aim + distance),
+                        (horizontal, depth, aim - distance),
+                    ] {
+                        if state.0 <= 6 && seen.insert(state) {
+                            next.push(state);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        for horizontal in 1..=6 {
+            for depth in -20..=20 {
+                let plan = plan((horizontal, depth)).unwrap();
+                assert_eq!(Some(&plan.len()), best.get(&(horizontal, depth)));
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_unreachable_targets() {
+        assert_eq!(plan((0, 5)), Err(PlanError::OutOfRange));
+        assert_eq!(plan((-3, 0)), Err(PlanError::Impossible));
+    }
+
+    #[test]
+    fn it_splits_legs_too_long_for_one_command() {
+        assert_eq!(
+            plan((70000, 0)),
+            Ok(vec![Command::Forward(65535), Command::Forward(4465)])
+        );
+        assert_eq!(
+            plan((1, 131071)),
+            Ok(vec![
+                Command::Down(65535),
+                Command::Down(65535),
+                Command::Down(1),
+                Command::Forward(1),
+            ])
+        );
+    }
+
+    #[test]
+    fn it_reaches_far_targets() {
+        for target in [
+            (70000, 0),
+            (2, 131071),
+            (70000, 70001),
+            (70000, -140000),
+            (1, i32::MAX),
+            (i32::MAX, i32::MIN),
+        ] {
+            let course: Vec<Line> = plan(target)
+                .unwrap()
+                .into_iter()
+                .enumerate()
+                .map(|(index, command)| Line {
+                    number: index + 1,
+                    command,
+                })
+                .collect();
+            let (horizontal, depth, _) = crate::p2_execute_movements(&course, (0, 0, 0)).unwrap();
+            assert_eq!((horizontal, depth), target);
+        }
+    }
+}
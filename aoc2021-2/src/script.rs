@@ -0,0 +1,476 @@
+//! Course scripts, which expand to plain commands before anything runs.
+//!
+//! A plain course is already a valid script. On top of that:
+//!
+//! ```text
+//! # comments run to the end of the line
+//! macro zigzag {
+//!     down 2
+//!     forward 3
+//!     up 2
+//! }
+//! repeat 4 { zigzag }
+//! again   # the previous command once more
+//! level   # undo every up and down so far
+//! ```
+//!
+//! Macros must be defined before they're used, and `again` and `level` are relative:
+//! what they expand to depends on the commands before them.
+
+use std::collections::HashMap;
+
+use crate::command::{Command, ErrorKind, Line, ParseError};
+
+/// Scripts that expand to more commands than this are rejected.
+const MAX_COMMANDS: usize = 1_000_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Statement {
+    Command(Command),
+    Repeat(u32, Vec<Spanned>),
+    Call(String),
+    Again,
+    Level,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    statement: Statement,
+    line: usize,
+    column: usize,
+}
+
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    for (index, line) in input.lines().enumerate() {
+        let mut rest = line;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                break;
+            }
+            let length = if rest.starts_with(['{', '}']) {
+                1
+            } else {
+                rest.find(|c: char| c.is_whitespace() || "{}#".contains(c))
+                    .unwrap_or(rest.len())
+            };
+            let offset = line.len() - rest.len();
+            tokens.push(Token {
+                text: &rest[..length],
+                line: index + 1,
+                column: line[..offset].chars().count() + 1,
+            });
+            rest = &rest[length..];
+        }
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    macros: HashMap<String, Vec<Spanned>>,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.position += token.is_some() as usize;
+        token
+    }
+
+    /// The next token, if it's on the same line as `token` and isn't a brace.
+    fn argument(&mut self, token: Token) -> Option<Token<'a>> {
+        match self.peek() {
+            Some(next) if next.line == token.line && !is_brace(next.text) => self.next(),
+            _ => None,
+        }
+    }
+
+    fn error(&mut self, token: Token, kind: ErrorKind) {
+        self.errors.push(ParseError {
+            line: token.line,
+            column: token.column,
+            kind,
+        });
+    }
+
+    /// Skips whatever is left of a line after an error.
+    fn skip_line(&mut self, line: usize) {
+        while self
+            .peek()
+            .is_some_and(|token| token.line == line && token.text != "}")
+        {
+            self.position += 1;
+        }
+    }
+
+    /// Statements up to the `}` matching `open`, or to the end for the top level.
+    fn block(&mut self, open: Option<Token>) -> Vec<Spanned> {
+        let mut statements = vec![];
+        loop {
+            match self.peek() {
+                None => {
+                    if let Some(open) = open {
+                        self.error(open, ErrorKind::Unclosed);
+                    }
+                    return statements;
+                }
+                Some(token) if token.text == "}" => {
+                    self.position += 1;
+                    if open.is_some() {
+                        return statements;
+                    }
+                    self.error(token, ErrorKind::UnexpectedBrace(token.text.to_string()));
+                }
+                Some(token) => {
+                    if let Some(statement) = self.statement() {
+                        statements.push(Spanned {
+                            statement,
+                            line: token.line,
+                            column: token.column,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// A `{ ... }` block, or `None` if there's no `{` next.
+    fn braced(&mut self, after: Token) -> Option<Vec<Spanned>> {
+        match self.peek() {
+            Some(open) if open.text == "{" => {
+                self.position += 1;
+                Some(self.block(Some(open)))
+            }
+            Some(token) => {
+                self.error(token, ErrorKind::ExpectedBrace(token.text.to_string()));
+                self.skip_line(token.line);
+                None
+            }
+            None => {
+                self.error(after, ErrorKind::ExpectedBrace(String::new()));
+                None
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Option<Statement> {
+        let token = self.next()?;
+        let statement = match token.text {
+            "repeat" => {
+                let count = match self.argument(token) {
+                    Some(count) => count.text.parse().unwrap_or_else(|_| {
+                        self.error(count, ErrorKind::BadCount(count.text.to_string()));
+                        0
+                    }),
+                    None => {
+                        self.error(token, ErrorKind::MissingCount);
+                        0
+                    }
+                };
+                return self
+                    .braced(token)
+                    .map(|body| Statement::Repeat(count, body));
+            }
+            "macro" => {
+                let name = self.argument(token);
+                let body = self.braced(token)?;
+                match name {
+                    None => self.error(token, ErrorKind::MissingName),
+                    Some(name) if is_keyword(name.text) => {
+                        self.error(name, ErrorKind::ReservedName(name.text.to_string()))
+                    }
+                    Some(name) if self.macros.contains_key(name.text) => {
+                        self.error(name, ErrorKind::Redefined(name.text.to_string()))
+                    }
+                    Some(name) => {
+                        self.macros.insert(name.text.to_string(), body);
+                    }
+                }
+                return None;
+            }
+            "again" => Statement::Again,
+            "level" => Statement::Level,
+            "forward" | "up" | "down" => {
+                let Some(distance) = self.argument(token) else {
+                    self.error(token, ErrorKind::MissingDistance(token.text.to_string()));
+                    return None;
+                };
+                match format!("{} {}", token.text, distance.text).parse() {
+                    Ok(command) => Statement::Command(command),
+                    Err(kind) => {
+                        self.error(distance, kind);
+                        self.skip_line(token.line);
+                        return None;
+                    }
+                }
+            }
+            "{" => {
+                self.error(token, ErrorKind::UnexpectedBrace(token.text.to_string()));
+                return None;
+            }
+            name if self.macros.contains_key(name) => Statement::Call(name.to_string()),
+            verb => {
+                self.error(token, ErrorKind::UnknownVerb(verb.to_string()));
+                self.skip_line(token.line);
+                return None;
+            }
+        };
+
+        // Only a closing brace can follow a command on its line
+        if let Some(extra) = self.argument(token) {
+            let rest: Vec<&str> = self.tokens[self.position - 1..]
+                .iter()
+                .take_while(|rest| rest.line == token.line && rest.text != "}")
+                .map(|rest| rest.text)
+                .collect();
+            self.error(extra, ErrorKind::ExtraTokens(rest.join(" ")));
+            self.skip_line(token.line);
+            return None;
+        }
+        Some(statement)
+    }
+}
+
+fn is_brace(text: &str) -> bool {
+    text == "{" || text == "}"
+}
+
+fn is_keyword(text: &str) -> bool {
+    matches!(
+        text,
+        "repeat" | "macro" | "again" | "level" | "forward" | "up" | "down"
+    )
+}
+
+struct Expander<'a> {
+    macros: &'a HashMap<String, Vec<Spanned>>,
+    commands: Vec<Line>,
+    /// Downs minus ups so far
+    vertical: i64,
+}
+
+impl Expander<'_> {
+    fn push(&mut self, command: Command, at: &Spanned) -> Result<(), ParseError> {
+        if self.commands.len() == MAX_COMMANDS {
+            return Err(ParseError {
+                line: at.line,
+                column: at.column,
+                kind: ErrorKind::TooLong(MAX_COMMANDS),
+            });
+        }
+        match command {
+            Command::Up(distance) => self.vertical -= distance as i64,
+            Command::Down(distance) => self.vertical += distance as i64,
+            Command::Forward(_) => {}
+        }
+        self.commands.push(Line {
+            number: at.line,
+            command,
+        });
+        Ok(())
+    }
+
+    fn expand(&mut self, statements: &[Spanned]) -> Result<(), ParseError> {
+        for spanned in statements {
+            match &spanned.statement {
+                Statement::Command(command) => self.push(*command, spanned)?,
+                Statement::Repeat(count, body) => {
+                    for _ in 0..*count {
+                        let before = (self.commands.len(), self.vertical);
+                        self.expand(body)?;
+                        // A body that added nothing will keep adding nothing
+                        if (self.commands.len(), self.vertical) == before {
+                            break;
+                        }
+                    }
+                }
+                Statement::Call(name) => {
+                    let macros = self.macros;
+                    self.expand(&macros[name])?
+                }
+                Statement::Again => match self.commands.last() {
+                    Some(line) => self.push(line.command, spanned)?,
+                    None => {
+                        return Err(ParseError {
+                            line: spanned.line,
+                            column: spanned.column,
+                            kind: ErrorKind::NothingToRepeat,
+                        })
+                    }
+                },
+                Statement::Level => {
+                    while self.vertical != 0 {
+                        let distance = self.vertical.unsigned_abs().min(u16::MAX as u64) as u16;
+                        let command = if self.vertical > 0 {
+                            Command::Up(distance)
+                        } else {
+                            Command::Down(distance)
+                        };
+                        self.push(command, spanned)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a script and expands it to plain commands, each with the line it came from,
+/// reporting every syntax error with its line and column.
+pub fn expand(input: &str) -> Result<Vec<Line>, Vec<ParseError>> {
+    let mut parser = Parser {
+        tokens: tokenize(input),
+        position: 0,
+        macros: HashMap::new(),
+        errors: vec![],
+    };
+    let statements = parser.block(None);
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+
+    let mut expander = Expander {
+        macros: &parser.macros,
+        commands: vec![],
+        vertical: 0,
+    };
+    expander.expand(&statements).map_err(|error| vec![error])?;
+    Ok(expander.commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(input: &str) -> Vec<Command> {
+        expand(input)
+            .unwrap()
+            .iter()
+            .map(|line| line.command)
+            .collect()
+    }
+
+    fn errors(input: &str) -> Vec<String> {
+        expand(input)
+            .unwrap_err()
+            .iter()
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_accepts_plain_courses() {
+        let commands = commands("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n");
+        assert_eq!(commands.len(), 6);
+        assert_eq!(commands[3], Command::Up(3));
+    }
+
+    #[test]
+    fn it_expands_repeats_and_macros() {
+        let script = "
+            # a gentle dive
+            macro step {
+                down 1   # steeper
+                forward 2
+            }
+            repeat 2 {
+                step
+                repeat 2 { forward 1 }
+            }
+        ";
+        assert_eq!(
+            commands(script),
+            vec![
+                Command::Down(1),
+                Command::Forward(2),
+                Command::Forward(1),
+                Command::Forward(1),
+                Command::Down(1),
+                Command::Forward(2),
+                Command::Forward(1),
+                Command::Forward(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_expands_relative_commands() {
+        let script = "down 5\nforward 2\nagain\nup 2\nlevel\nlevel";
+        assert_eq!(
+            commands(script),
+            vec![
+                Command::Down(5),
+                Command::Forward(2),
+                Command::Forward(2),
+                Command::Up(2),
+                Command::Up(3),
+            ]
+        );
+        assert_eq!(
+            errors("  again"),
+            vec!["line 1, column 3: nothing to repeat yet"]
+        );
+    }
+
+    #[test]
+    fn it_keeps_each_commands_line() {
+        let script = "macro step {\n  forward 1\n}\nrepeat 2 { step }\ndown 3\nagain\nlevel";
+        let lines: Vec<usize> = expand(script)
+            .unwrap()
+            .iter()
+            .map(|line| line.number)
+            .collect();
+        // Macro bodies point at the definition, and relative commands at themselves
+        assert_eq!(lines, vec![2, 2, 5, 6, 7]);
+    }
+
+    #[test]
+    fn it_points_at_syntax_errors() {
+        assert_eq!(
+            errors("forward 5\nrepeat x {\n  forward 1 metres\n  dwon 2\n}\n}"),
+            vec![
+                "line 2, column 8: couldn't parse repeat count \"x\"",
+                "line 3, column 13: unexpected \"metres\" after the distance",
+                "line 4, column 3: unknown command \"dwon\"",
+                "line 6, column 1: unexpected \"}\"",
+            ]
+        );
+        assert_eq!(
+            errors("repeat 2 {\n  forward 1"),
+            vec!["line 1, column 10: \"{\" is never closed"]
+        );
+        assert_eq!(
+            errors("zig\nmacro zig { up 1 }\nmacro zig { up 2 }"),
+            vec![
+                "line 1, column 1: unknown command \"zig\"",
+                "line 3, column 7: macro \"zig\" is already defined",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_limits_expansion() {
+        assert_eq!(expand("repeat 4000000000 { level }"), Ok(vec![]));
+
+        let script = "repeat 1000 { repeat 1000 { repeat 1000 { forward 1 } } }";
+        assert_eq!(
+            errors(script),
+            vec!["line 1, column 43: expands to more than 1000000 commands"]
+        );
+    }
+}
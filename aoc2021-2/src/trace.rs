@@ -0,0 +1,224 @@
+use std::fmt::Write;
+
+use aoc_common::checked::{Location, Overflow};
+
+use crate::command::{Command, Line};
+use crate::motion::{MotionModel, Snapshot};
+
+/// Where the submarine was after one command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    /// 1-based
+    pub step: usize,
+    /// The input line the command came from
+    pub line: usize,
+    pub command: Command,
+    pub position: Snapshot,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub max_depth: i64,
+    /// The first step that reached `max_depth`, or 0 if it's the starting depth
+    pub max_depth_step: usize,
+    /// Every step that left the submarine above the surface
+    pub above_surface: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub start: Snapshot,
+    pub steps: Vec<Step>,
+}
+
+/// Drives a course like [`crate::motion::drive`], keeping the position after every
+/// command.
+pub fn trace<M: MotionModel>(
+    model: &M,
+    commands: &[Line],
+    start: M::State,
+) -> Result<Trace, Overflow> {
+    let mut state = start;
+    let mut steps = Vec::with_capacity(commands.len());
+    for (index, line) in commands.iter().enumerate() {
+        state = model.step(state, line.command, Location::line(2, line.number))?;
+        steps.push(Step {
+            step: index + 1,
+            line: line.number,
+            command: line.command,
+            position: model.snapshot(&state),
+        });
+    }
+
+    Ok(Trace {
+        start: model.snapshot(&start),
+        steps,
+    })
+}
+
+impl Trace {
+    pub fn summary(&self) -> Summary {
+        let mut max_depth = self.start.depth;
+        let mut max_depth_step = 0;
+        for step in &self.steps {
+            if step.position.depth > max_depth {
+                max_depth = step.position.depth;
+                max_depth_step = step.step;
+            }
+        }
+
+        Summary {
+            max_depth,
+            max_depth_step,
+            above_surface: self
+                .steps
+                .iter()
+                .filter(|step| step.position.depth < 0)
+                .map(|step| step.step)
+                .collect(),
+        }
+    }
+
+    /// One row per step, with an empty aim for models that don't have one.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "step,line,command,horizontal,depth,aim\n".to_string();
+        for step in &self.steps {
+            let aim = step.position.aim.map(|aim| aim.to_string());
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                step.step,
+                step.line,
+                step.command,
+                step.position.horizontal,
+                step.position.depth,
+                aim.unwrap_or_default()
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// The steps and their summary, one step per line.
+    pub fn to_json(&self) -> String {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| {
+                let aim = match step.position.aim {
+                    Some(aim) => aim.to_string(),
+                    None => "null".to_string(),
+                };
+                format!(
+                    r#"    {{"step": {}, "line": {}, "command": "{}", "horizontal": {}, "depth": {}, "aim": {}}}"#,
+                    step.step,
+                    step.line,
+                    step.command,
+                    step.position.horizontal,
+                    step.position.depth,
+                    aim
+                )
+            })
+            .collect();
+        let summary = self.summary();
+        let above_surface: Vec<String> = summary
+            .above_surface
+            .iter()
+            .map(|step| step.to_string())
+            .collect();
+
+        format!(
+            "{{\n  \"steps\": [\n{}\n  ],\n  \"summary\": {{\"max_depth\": {}, \"max_depth_step\": {}, \"above_surface\": [{}]}}\n}}\n",
+            steps.join(",\n"),
+            summary.max_depth,
+            summary.max_depth_step,
+            above_surface.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::parse_commands;
+    use crate::motion::{PartTwo, SignedPartOne};
+
+    const INPUT: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
+    #[test]
+    fn it_records_every_step() {
+        let commands = parse_commands(INPUT).unwrap();
+        let trace = trace(&PartTwo, &commands, (0, 0, 0)).unwrap();
+        assert_eq!(trace.steps.len(), 6);
+        assert_eq!(
+            trace.steps[2],
+            Step {
+                step: 3,
+                line: 3,
+                command: Command::Forward(8),
+                position: Snapshot {
+                    horizontal: 13,
+                    depth: 40,
+                    aim: Some(5),
+                },
+            }
+        );
+        assert_eq!(
+            trace.summary(),
+            Summary {
+                max_depth: 60,
+                max_depth_step: 6,
+                above_surface: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn it_finds_where_the_submarine_surfaced() {
+        let commands = parse_commands("forward 5\ndown 2\nup 3\ndown 4").unwrap();
+        let trace = trace(&SignedPartOne, &commands, (0, 0)).unwrap();
+        let summary = trace.summary();
+        assert_eq!(summary.above_surface, vec![3]);
+        assert_eq!(summary.max_depth, 3);
+        assert_eq!(summary.max_depth_step, 4);
+    }
+
+    #[test]
+    fn it_counts_steps_apart_from_lines() {
+        let commands = parse_commands("# a dive\nrepeat 2 { down 1 }\nforward 3").unwrap();
+        let trace = trace(&SignedPartOne, &commands, (0, 0)).unwrap();
+        let steps: Vec<(usize, usize)> = trace
+            .steps
+            .iter()
+            .map(|step| (step.step, step.line))
+            .collect();
+        assert_eq!(steps, vec![(1, 2), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn it_exports_csv() {
+        let commands = parse_commands("forward 5\nup 3").unwrap();
+        let csv = trace(&SignedPartOne, &commands, (0, 0)).unwrap().to_csv();
+        assert_eq!(
+            csv,
+            "step,line,command,horizontal,depth,aim\n1,1,forward 5,5,0,\n2,2,up 3,5,-3,\n"
+        );
+    }
+
+    #[test]
+    fn it_exports_json() {
+        let commands = parse_commands("down 2\nforward 3").unwrap();
+        let json = trace(&PartTwo, &commands, (0, 0, 0)).unwrap().to_json();
+        assert_eq!(
+            json,
+            r#"{
+  "steps": [
+    {"step": 1, "line": 1, "command": "down 2", "horizontal": 0, "depth": 0, "aim": 2},
+    {"step": 2, "line": 2, "command": "forward 3", "horizontal": 3, "depth": 6, "aim": 2}
+  ],
+  "summary": {"max_depth": 6, "max_depth_step": 2, "above_surface": []}
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+use std::ops::Mul;
+
+/// An unsigned integer of any size, so readings and ratings aren't limited to 64 bits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BigUint {
+    /// Least significant first, with no trailing zero limbs, so zero has none
+    limbs: Vec<u64>,
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut number = BigUint { limbs: vec![value] };
+        number.normalize();
+        number
+    }
+}
+
+impl BigUint {
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    /// Shifts left by one and sets the new lowest bit, for building a number up from
+    /// its most significant bit.
+    pub fn push_bit(&mut self, bit: u64) {
+        let mut carry = bit;
+        for limb in &mut self.limbs {
+            let top = *limb >> 63;
+            *limb = *limb << 1 | carry;
+            carry = top;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    /// The bit worth `2^index`.
+    pub fn bit(&self, index: usize) -> u64 {
+        self.limbs
+            .get(index / 64)
+            .map_or(0, |limb| limb >> (index % 64) & 1)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Divides in place, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u64) -> u64 {
+        let mut remainder = 0u128;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 64 | *limb as u128;
+            *limb = (value / divisor as u128) as u64;
+            remainder = value % divisor as u128;
+        }
+        self.normalize();
+        remainder as u64
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u64; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let value = *a as u128 * *b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = value as u64;
+                carry = value >> 64;
+            }
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+        let mut product = BigUint { limbs };
+        product.normalize();
+        product
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Nineteen decimal digits at a time, the most that fit in a u64
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self.clone();
+        let mut chunks = vec![];
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(CHUNK));
+        }
+
+        let mut digits = match chunks.pop() {
+            Some(top) => top.to_string(),
+            None => "0".to_string(),
+        };
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:019}", chunk));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut digits = match self.limbs.last() {
+            Some(top) => format!("{:x}", top),
+            None => "0".to_string(),
+        };
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:016x}", limb));
+        }
+        f.pad_integral(true, "0x", &digits)
+    }
+}
+
+impl fmt::Binary for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut digits = match self.limbs.last() {
+            Some(top) => format!("{:b}", top),
+            None => "0".to_string(),
+        };
+        for limb in self.limbs.iter().rev().skip(1) {
+            digits.push_str(&format!("{:064b}", limb));
+        }
+        f.pad_integral(true, "0b", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_bits(bits: &str) -> BigUint {
+        let mut number = BigUint::default();
+        for bit in bits.chars() {
+            number.push_bit(bit.to_digit(2).unwrap() as u64);
+        }
+        number
+    }
+
+    #[test]
+    fn it_builds_numbers_bit_by_bit() {
+        assert_eq!(from_bits("10111"), BigUint::from(23));
+        assert_eq!(from_bits("0000"), BigUint::from(0));
+
+        // 2^64 + 1
+        let wide = from_bits(&format!("1{}1", "0".repeat(63)));
+        assert_eq!(wide.bit(0), 1);
+        assert_eq!(wide.bit(64), 1);
+        assert_eq!(wide.bit(63), 0);
+        assert_eq!(wide.bit(1000), 0);
+    }
+
+    #[test]
+    fn it_multiplies() {
+        let max = BigUint::from(u64::MAX);
+        // (2^64 - 1)^2 = 2^128 - 2^65 + 1
+        assert_eq!(
+            (&max * &max).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(&max * &BigUint::from(0), BigUint::from(0));
+        assert_eq!((&BigUint::from(22) * &BigUint::from(9)).to_string(), "198");
+    }
+
+    #[test]
+    fn it_prints_in_any_base() {
+        // 2^64 + 1
+        let wide = from_bits(&format!("1{}1", "0".repeat(63)));
+        assert_eq!(wide.to_string(), "18446744073709551617");
+        assert_eq!(format!("{:#x}", wide), "0x10000000000000001");
+        assert_eq!(format!("{:b}", wide).len(), 65);
+
+        let small = BigUint::from(5);
+        assert_eq!(format!("{:05b}", small), "00101");
+        assert_eq!(format!("{:x}", BigUint::from(0)), "0");
+        assert_eq!(format!("{:>4}", small), "   5");
+    }
+}
@@ -0,0 +1,184 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::big::BigUint;
+use crate::report::Report;
+use crate::trie::Trie;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Keep {
+    MostCommon,
+    LeastCommon,
+}
+
+impl FromStr for Keep {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "most" => Ok(Keep::MostCommon),
+            "least" => Ok(Keep::LeastCommon),
+            _ => Err(format!("expected most or least, found {:?}", s)),
+        }
+    }
+}
+
+/// How to whittle a report down to one reading, a column at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Criteria {
+    pub keep: Keep,
+    /// The bit kept when both are equally common
+    pub tie: u64,
+    /// The columns to filter on, counting from 0 on the left. Left to right if `None`.
+    pub order: Option<Vec<usize>>,
+}
+
+impl Criteria {
+    pub const OXYGEN_GENERATOR: Criteria = Criteria {
+        keep: Keep::MostCommon,
+        tie: 1,
+        order: None,
+    };
+
+    pub const CO2_SCRUBBER: Criteria = Criteria {
+        keep: Keep::LeastCommon,
+        tie: 0,
+        order: None,
+    };
+
+    /// Reads `oxygen`, `co2` or `most|least TIE [--order COLUMN,COLUMN,...]`.
+    pub fn parse(args: &[String], width: usize) -> Result<Criteria, String> {
+        let (keep, tie, rest) = match args {
+            [name] if name == "oxygen" => return Ok(Criteria::OXYGEN_GENERATOR),
+            [name] if name == "co2" => return Ok(Criteria::CO2_SCRUBBER),
+            [keep, tie, rest @ ..] => (keep.parse()?, tie, rest),
+            _ => return Err("expected most or least and a tie-break bit".to_string()),
+        };
+        let tie = match tie.as_str() {
+            "0" => 0,
+            "1" => 1,
+            _ => return Err(format!("the tie-break bit can't be {:?}", tie)),
+        };
+
+        let order = match rest {
+            [] => None,
+            [flag, columns] if flag == "--order" => {
+                let columns: Vec<usize> = columns
+                    .split(',')
+                    .map(|column| {
+                        column
+                            .parse()
+                            .map_err(|_| format!("bad column {:?}", column))
+                    })
+                    .collect::<Result<_, _>>()?;
+                for (index, column) in columns.iter().enumerate() {
+                    if *column >= width {
+                        return Err(format!("column {} is past the last column", column));
+                    }
+                    if columns[..index].contains(column) {
+                        return Err(format!("column {} comes up twice", column));
+                    }
+                }
+                Some(columns)
+            }
+            _ => return Err("expected --order COLUMN,COLUMN,...".to_string()),
+        };
+
+        Ok(Criteria { keep, tie, order })
+    }
+
+    /// The bit kept in a column with these counts. Only bits that are there can be
+    /// kept, so the least common bit of a column that's all ones is still 1.
+    pub fn choose(&self, zeroes: usize, ones: usize) -> u64 {
+        if zeroes == 0 || ones == 0 {
+            return (ones > 0) as u64;
+        }
+        match (self.keep, zeroes.cmp(&ones)) {
+            (_, Ordering::Equal) => self.tie,
+            (Keep::MostCommon, order) => (order == Ordering::Less) as u64,
+            (Keep::LeastCommon, order) => (order == Ordering::Greater) as u64,
+        }
+    }
+
+    pub fn columns(&self, width: usize) -> Vec<usize> {
+        match &self.order {
+            Some(order) => order.clone(),
+            None => (0..width).collect(),
+        }
+    }
+
+    /// Filters the report column by column until one reading is left, or the columns
+    /// run out, and returns the first survivor. Walks a [`Trie`] rather than actually
+    /// filtering, which [`crate::explain::explain`] does.
+    pub fn rate(&self, report: &Report) -> Option<BigUint> {
+        let index = Trie::new(report, &self.columns(report.width)).walk(self)?;
+        Some(report.readings[index].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
+    fn args(input: &str) -> Vec<String> {
+        input
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_rates_the_example() {
+        let report = Report::parse(INPUT).unwrap();
+        assert_eq!(
+            Criteria::OXYGEN_GENERATOR.rate(&report),
+            Some(BigUint::from(0b10111))
+        );
+        assert_eq!(
+            Criteria::CO2_SCRUBBER.rate(&report),
+            Some(BigUint::from(0b01010))
+        );
+    }
+
+    #[test]
+    fn it_breaks_ties() {
+        assert_eq!(Criteria::OXYGEN_GENERATOR.choose(3, 3), 1);
+        assert_eq!(Criteria::CO2_SCRUBBER.choose(3, 3), 0);
+        assert_eq!(Criteria::OXYGEN_GENERATOR.choose(4, 2), 0);
+        assert_eq!(Criteria::CO2_SCRUBBER.choose(4, 2), 1);
+        // Nothing to choose between
+        assert_eq!(Criteria::CO2_SCRUBBER.choose(0, 2), 1);
+    }
+
+    #[test]
+    fn it_filters_in_any_order() {
+        let report = Report::parse(INPUT).unwrap();
+        let criteria = Criteria::parse(&args("most 1 --order 4,3,2,1,0"), 5).unwrap();
+        // Zeroes win the last column 7 to 5, then ones win the fourth 4 to 3, and the
+        // ties in the third and second both go to 1
+        assert_eq!(criteria.rate(&report), Some(BigUint::from(0b11110)));
+
+        let criteria = Criteria::parse(&args("least 0 --order 1"), 5).unwrap();
+        // Only the second column is used, so every reading with a 1 there is left
+        assert_eq!(criteria.rate(&report), Some(BigUint::from(0b11110)));
+    }
+
+    #[test]
+    fn it_parses_criteria() {
+        assert_eq!(
+            Criteria::parse(&args("least 0"), 5),
+            Ok(Criteria::CO2_SCRUBBER)
+        );
+        assert_eq!(
+            Criteria::parse(&args("oxygen"), 5),
+            Ok(Criteria::OXYGEN_GENERATOR)
+        );
+        assert!(Criteria::parse(&args("most 2"), 5).is_err());
+        assert!(Criteria::parse(&args("common 1"), 5).is_err());
+        assert!(Criteria::parse(&args("most 1 --order 5"), 5).is_err());
+        assert!(Criteria::parse(&args("most 1 --order 1,1"), 5).is_err());
+    }
+}
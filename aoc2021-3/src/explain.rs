@@ -0,0 +1,175 @@
+use std::fmt::Write;
+
+use crate::big::BigUint;
+use crate::criteria::Criteria;
+use crate::report::Report;
+
+/// One column's worth of filtering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Round {
+    /// Counting from 0 on the left
+    pub column: usize,
+    pub zeroes: usize,
+    pub ones: usize,
+    /// The bit readings needed in this column to survive
+    pub kept: u64,
+    pub survivors: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub width: usize,
+    pub rounds: Vec<Round>,
+    pub rating: Option<BigUint>,
+}
+
+/// Filters the report like [`Criteria::rate`], keeping the counts behind every choice.
+pub fn explain(criteria: &Criteria, report: &Report) -> Explanation {
+    let mut candidates = report.readings.clone();
+    let mut rounds = vec![];
+    for column in criteria.columns(report.width) {
+        if candidates.len() <= 1 {
+            break;
+        }
+        let bit = report.bit(column);
+        let ones = candidates
+            .iter()
+            .filter(|reading| reading.bit(bit) == 1)
+            .count();
+        let zeroes = candidates.len() - ones;
+        let kept = criteria.choose(zeroes, ones);
+        candidates = crate::filter_reading(&candidates, kept, bit);
+        rounds.push(Round {
+            column,
+            zeroes,
+            ones,
+            kept,
+            survivors: candidates.len(),
+        });
+    }
+
+    Explanation {
+        width: report.width,
+        rounds,
+        rating: candidates.first().cloned(),
+    }
+}
+
+impl Explanation {
+    /// One row per round, then the rating in binary and decimal.
+    pub fn to_table(&self) -> String {
+        let mut table = "column  zeroes    ones  kept  survivors\n".to_string();
+        for round in &self.rounds {
+            writeln!(
+                table,
+                "{:>6}  {:>6}  {:>6}  {:>4}  {:>9}",
+                round.column, round.zeroes, round.ones, round.kept, round.survivors
+            )
+            .unwrap();
+        }
+        match &self.rating {
+            Some(rating) => writeln!(
+                table,
+                "rating {:0width$b} ({})",
+                rating,
+                rating,
+                width = self.width
+            )
+            .unwrap(),
+            None => writeln!(table, "no rating").unwrap(),
+        }
+        table
+    }
+
+    /// The rounds and the rating, one round per line. The rating is a decimal string,
+    /// since readings can be wider than JSON numbers are precise.
+    pub fn to_json(&self) -> String {
+        let rounds: Vec<String> = self
+            .rounds
+            .iter()
+            .map(|round| {
+                format!(
+                    r#"    {{"column": {}, "zeroes": {}, "ones": {}, "kept": {}, "survivors": {}}}"#,
+                    round.column, round.zeroes, round.ones, round.kept, round.survivors
+                )
+            })
+            .collect();
+        let rating = match &self.rating {
+            Some(rating) => format!("\"{}\"", rating),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\n  \"rounds\": [\n{}\n  ],\n  \"rating\": {}\n}}\n",
+            rounds.join(",\n"),
+            rating
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
+    #[test]
+    fn it_explains_every_round() {
+        let report = Report::parse(INPUT).unwrap();
+        let explanation = explain(&Criteria::OXYGEN_GENERATOR, &report);
+        let survivors: Vec<usize> = explanation
+            .rounds
+            .iter()
+            .map(|round| round.survivors)
+            .collect();
+        assert_eq!(survivors, vec![7, 4, 3, 2, 1]);
+        // The last round is the tie between 10110 and 10111
+        assert_eq!(
+            explanation.rounds[4],
+            Round {
+                column: 4,
+                zeroes: 1,
+                ones: 1,
+                kept: 1,
+                survivors: 1,
+            }
+        );
+        assert_eq!(explanation.rating, Some(BigUint::from(23)));
+    }
+
+    #[test]
+    fn it_exports_a_table() {
+        let report = Report::parse("101\n100\n011").unwrap();
+        let table = explain(&Criteria::CO2_SCRUBBER, &report).to_table();
+        assert_eq!(
+            table,
+            "column  zeroes    ones  kept  survivors
+     0       1       2     0          1
+rating 011 (3)
+"
+        );
+    }
+
+    #[test]
+    fn it_exports_json() {
+        let report = Report::parse("101\n100\n011").unwrap();
+        let json = explain(&Criteria::OXYGEN_GENERATOR, &report).to_json();
+        assert_eq!(
+            json,
+            r#"{
+  "rounds": [
+    {"column": 0, "zeroes": 1, "ones": 2, "kept": 1, "survivors": 2},
+    {"column": 1, "zeroes": 2, "ones": 0, "kept": 0, "survivors": 2},
+    {"column": 2, "zeroes": 1, "ones": 1, "kept": 1, "survivors": 1}
+  ],
+  "rating": "5"
+}
+"#
+        );
+
+        // 70 ones, far past the 2^53 a JSON number can hold exactly
+        let report = Report::parse(&"1".repeat(70)).unwrap();
+        let json = explain(&Criteria::OXYGEN_GENERATOR, &report).to_json();
+        assert!(json.ends_with("\"rating\": \"1180591620717411303423\"\n}\n"));
+    }
+}
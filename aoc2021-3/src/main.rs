@@ -1,228 +1,232 @@
-use std::fs;
+mod big;
+mod criteria;
+mod explain;
+mod report;
+mod stats;
+mod trie;
+
+use aoc_common::input::read_input;
+use big::BigUint;
+use criteria::Criteria;
+use report::Report;
+use std::env;
+use std::process;
+
+const USAGE: &str = r#"usage:
+    aoc2021-3
+    aoc2021-3 --rating CRITERIA
+    aoc2021-3 --explain (table | json) CRITERIA
+    aoc2021-3 --validate
+    aoc2021-3 --stats
+
+criteria: oxygen, co2, (most | least) TIE [--order COLUMN,COLUMN,...]
+columns count from 0 on the left, and are filtered left to right by default"#;
 
 fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("couldn't read the file")
-        .lines()
-        .map(|line| line.parse().expect("couldn't parse line"))
-        .collect();
-
-    let aggregate = aggregate_readings(&input, 12);
-    let gamma_rate = get_gamma_rate(&aggregate);
-    let episilon_rate = get_epsilon_rate(&aggregate);
-    let decimal_gamma = binary_to_decimal(gamma_rate);
-    let decimal_epsilon = binary_to_decimal(episilon_rate);
-
-    let o2_generator_rating = get_oxygen_generator_rating(&input, 12);
-    let co2_dioxide_scrubber_rating = get_carbon_dioxide_scrubber_rating(&input, 12);
-    let decimal_o2 = binary_to_decimal(o2_generator_rating);
-    let decimal_co2 = binary_to_decimal(co2_dioxide_scrubber_rating);
-
-    dbg!(decimal_gamma * decimal_epsilon);
-    dbg!(decimal_o2 * decimal_co2);
-}
+    let args: Vec<String> = env::args().skip(1).collect();
+    let input = read_input();
+    if let [flag] = args.as_slice() {
+        if flag == "--validate" {
+            validate(&input);
+            return;
+        }
+    }
 
-fn aggregate_readings(input: &[String], size: usize) -> Vec<(i64, i64)> {
-    let mut output = vec![(0, 0); size];
-    for reading in input {
-        for (i, c) in reading.chars().enumerate() {
-            if c == '1' {
-                output[i].1 += 1;
-            } else {
-                output[i].0 += 1;
+    let report = Report::parse(&input).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+
+    match args.as_slice() {
+        [] => {}
+        [flag, criteria @ ..] if flag == "--rating" => {
+            let rating = parse_criteria(criteria, &report)
+                .rate(&report)
+                .expect("the report has readings");
+            println!("{:0width$b} ({})", rating, rating, width = report.width);
+            return;
+        }
+        [flag] if flag == "--stats" => {
+            print!("{}", stats::statistics(&report));
+            return;
+        }
+        [flag, format, criteria @ ..] if flag == "--explain" => {
+            let explanation = explain::explain(&parse_criteria(criteria, &report), &report);
+            match format.as_str() {
+                "table" => print!("{}", explanation.to_table()),
+                "json" => print!("{}", explanation.to_json()),
+                _ => usage(),
             }
+            return;
         }
+        _ => usage(),
     }
-    output
+
+    let aggregate = aggregate_readings(&report);
+    let gamma_rate = get_gamma_rate(&aggregate);
+    let epsilon_rate = get_epsilon_rate(&aggregate);
+
+    let o2_generator_rating = get_oxygen_generator_rating(&report);
+    let co2_scrubber_rating = get_carbon_dioxide_scrubber_rating(&report);
+
+    let power_consumption = &gamma_rate * &epsilon_rate;
+    let life_support_rating = &o2_generator_rating * &co2_scrubber_rating;
+    println!("part 1: {} ({:#x})", power_consumption, power_consumption);
+    println!(
+        "part 2: {} ({:#x})",
+        life_support_rating, life_support_rating
+    );
 }
 
-fn get_gamma_rate(aggregate: &[(i64, i64)]) -> String {
-    let mut output = "".to_string();
+/// The `(zeroes, ones)` in each column, leftmost first.
+fn aggregate_readings(report: &Report) -> Vec<(i64, i64)> {
+    (0..report.width)
+        .map(|column| {
+            let bit = report.bit(column);
+            let ones = report
+                .readings
+                .iter()
+                .filter(|reading| reading.bit(bit) == 1)
+                .count() as i64;
+            (report.readings.len() as i64 - ones, ones)
+        })
+        .collect()
+}
+
+fn get_gamma_rate(aggregate: &[(i64, i64)]) -> BigUint {
+    let mut output = BigUint::default();
     for (zeroes, ones) in aggregate {
-        if zeroes > ones {
-            output.push('0');
-        } else {
-            output.push('1');
-        }
+        output.push_bit((zeroes <= ones) as u64);
     }
     output
 }
 
-fn get_epsilon_rate(aggregate: &[(i64, i64)]) -> String {
-    let mut output = "".to_string();
+fn get_epsilon_rate(aggregate: &[(i64, i64)]) -> BigUint {
+    let mut output = BigUint::default();
     for (zeroes, ones) in aggregate {
-        if ones < zeroes {
-            output.push('1');
-        } else {
-            output.push('0');
-        }
+        output.push_bit((ones < zeroes) as u64);
     }
     output
 }
 
-fn binary_to_decimal(input: String) -> isize {
-    let str = input.as_str();
-    isize::from_str_radix(str, 2).expect("couldn't parse binary")
+fn filter_reading(input: &[BigUint], mask: u64, bit: usize) -> Vec<BigUint> {
+    input
+        .iter()
+        .filter(|reading| reading.bit(bit) == mask)
+        .cloned()
+        .collect()
 }
 
-fn filter_reading(input: &[String], mask: char, index: usize) -> Vec<String> {
-    let mut output: Vec<String> = vec![];
-    for reading in input {
-        if let Some(r) = reading.chars().nth(index) {
-            if r == mask {
-                output.push(reading.clone());
-            }
-        }
-    }
-    output
+fn get_oxygen_generator_rating(report: &Report) -> BigUint {
+    Criteria::OXYGEN_GENERATOR
+        .rate(report)
+        .expect("the report has readings")
 }
 
-fn get_oxygen_generator_rating(input: &[String], size: usize) -> String {
-    let mut output = vec!["".to_string(); input.len()];
-    output.clone_from_slice(input);
-
-    // Get initial gamma_rate
-    let aggregate = aggregate_readings(&output, size);
-    let mut gamma_rate = get_gamma_rate(&aggregate);
-
-    for index in 0..gamma_rate.len() {
-        // If there's only one output remaining, exit early
-        if output.len() == 1 {
-            break;
-        }
+fn get_carbon_dioxide_scrubber_rating(report: &Report) -> BigUint {
+    Criteria::CO2_SCRUBBER
+        .rate(report)
+        .expect("the report has readings")
+}
 
-        // Otherwise, filter the remaining inputs by the given mask in gamma rate
-        if let Some(mask) = gamma_rate.chars().nth(index) {
-            output = filter_reading(&output, mask, index);
-            // Recalculate gamma_rate
-            let aggregate = aggregate_readings(&output, size);
-            gamma_rate = get_gamma_rate(&aggregate);
-        }
+/// Prints every problem with the report, failing if there are any.
+fn validate(input: &str) {
+    let errors = report::validate(input);
+    for error in &errors {
+        println!("{}", error);
     }
-
-    output[0].clone()
+    if !errors.is_empty() {
+        process::exit(1);
+    }
+    println!("no problems with {} readings", input.lines().count());
 }
 
-fn get_carbon_dioxide_scrubber_rating(input: &[String], size: usize) -> String {
-    let mut output = vec!["".to_string(); input.len()];
-    output.clone_from_slice(input);
-
-    // Get initial epsilon_rate
-    let aggregate = aggregate_readings(&output, size);
-    let mut epsilon_rate = get_epsilon_rate(&aggregate);
-
-    for index in 0..epsilon_rate.len() {
-        // If there's only one output remaining, exit early
-        if output.len() == 1 {
-            break;
-        }
-
-        // Otherwise, filter the remaining inputs by the given mask in epsilon rate
-        if let Some(mask) = epsilon_rate.chars().nth(index) {
-            output = filter_reading(&output, mask, index);
-            // Recalculate epsilon rate
-            let aggregate = aggregate_readings(&output, size);
-            epsilon_rate = get_epsilon_rate(&aggregate);
-        }
-    }
+fn parse_criteria(args: &[String], report: &Report) -> Criteria {
+    Criteria::parse(args, report.width).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        usage();
+    })
+}
 
-    output[0].clone()
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+
+    const INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
     #[test]
     fn it_finds_the_gamma_rate() {
-        let input = vec![
-            "00100".to_string(),
-            "11110".to_string(),
-            "10110".to_string(),
-            "10111".to_string(),
-            "10101".to_string(),
-            "01111".to_string(),
-            "00111".to_string(),
-            "11100".to_string(),
-            "10000".to_string(),
-            "11001".to_string(),
-            "00010".to_string(),
-            "01010".to_string(),
-        ];
-
-        let aggregate = aggregate_readings(&input, 5);
+        let report = Report::parse(INPUT).unwrap();
+        let aggregate = aggregate_readings(&report);
         let gamma_rate = get_gamma_rate(&aggregate);
-        assert_eq!(gamma_rate, "10110");
+        assert_eq!(gamma_rate, BigUint::from(0b10110));
     }
 
     #[test]
     fn it_finds_the_epsilion_rate() {
-        let input = vec![
-            "00100".to_string(),
-            "11110".to_string(),
-            "10110".to_string(),
-            "10111".to_string(),
-            "10101".to_string(),
-            "01111".to_string(),
-            "00111".to_string(),
-            "11100".to_string(),
-            "10000".to_string(),
-            "11001".to_string(),
-            "00010".to_string(),
-            "01010".to_string(),
-        ];
-
-        let aggregate = aggregate_readings(&input, 5);
+        let report = Report::parse(INPUT).unwrap();
+        let aggregate = aggregate_readings(&report);
         let epsilon_rate = get_epsilon_rate(&aggregate);
-        assert_eq!(epsilon_rate, "01001");
+        assert_eq!(epsilon_rate, BigUint::from(0b01001));
     }
 
     #[test]
-    fn it_converts_binary_to_decimal() {
-        let input = "10110".to_string();
-        assert_eq!(binary_to_decimal(input), 22);
+    fn it_filters_by_bit() {
+        let report = Report::parse(INPUT).unwrap();
+        // Readings with a 1 in the second column
+        let output = filter_reading(&report.readings, 1, report.bit(1));
+        let expected: Vec<BigUint> = [0b11110, 0b01111, 0b11100, 0b11001, 0b01010]
+            .into_iter()
+            .map(BigUint::from)
+            .collect();
+        assert_eq!(output, expected);
     }
 
     #[test]
     fn it_gets_the_oxygen_generator_rating() {
-        let input = vec![
-            "00100".to_string(),
-            "11110".to_string(),
-            "10110".to_string(),
-            "10111".to_string(),
-            "10101".to_string(),
-            "01111".to_string(),
-            "00111".to_string(),
-            "11100".to_string(),
-            "10000".to_string(),
-            "11001".to_string(),
-            "00010".to_string(),
-            "01010".to_string(),
-        ];
-
-        let output = get_oxygen_generator_rating(&input, 5);
-        assert_eq!(output, "10111".to_string());
-        assert_eq!(binary_to_decimal(output), 23);
+        let report = Report::parse(INPUT).unwrap();
+        let output = get_oxygen_generator_rating(&report);
+        assert_eq!(output, BigUint::from(0b10111));
+        assert_eq!(output.to_string(), "23");
     }
 
     #[test]
     fn it_gets_the_carbon_dioxide_scrubber_rating() {
-        let input = vec![
-            "00100".to_string(),
-            "11110".to_string(),
-            "10110".to_string(),
-            "10111".to_string(),
-            "10101".to_string(),
-            "01111".to_string(),
-            "00111".to_string(),
-            "11100".to_string(),
-            "10000".to_string(),
-            "11001".to_string(),
-            "00010".to_string(),
-            "01010".to_string(),
-        ];
-
-        let output = get_carbon_dioxide_scrubber_rating(&input, 5);
-        assert_eq!(output, "01010".to_string());
-        assert_eq!(binary_to_decimal(output), 10);
+        let report = Report::parse(INPUT).unwrap();
+        let output = get_carbon_dioxide_scrubber_rating(&report);
+        assert_eq!(output, BigUint::from(0b01010));
+        assert_eq!(output.to_string(), "10");
+    }
+
+    #[test]
+    fn it_handles_readings_wider_than_64_bits() {
+        let input = format!(
+            "{}\n1{}\n{}",
+            "1".repeat(70),
+            "0".repeat(69),
+            "0".repeat(70)
+        );
+        let report = Report::parse(&input).unwrap();
+        let aggregate = aggregate_readings(&report);
+        // Only the first column has more ones than zeroes, so gamma is 2^69
+        let power_consumption = &get_gamma_rate(&aggregate) * &get_epsilon_rate(&aggregate);
+        assert_eq!(
+            power_consumption.to_string(),
+            "348449143727040986585905302199771942879232"
+        );
+        assert_eq!(
+            format!("{:#x}", power_consumption),
+            "0x3ffffffffffffffffe00000000000000000"
+        );
+        assert_eq!(
+            format!("{:x}", get_oxygen_generator_rating(&report)),
+            "3fffffffffffffffff"
+        );
     }
 }
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::big::BigUint;
+
+/// A diagnostic report, with every reading packed into an integer of any width. The
+/// first column of a reading is its most significant bit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub width: usize,
+    pub readings: Vec<BigUint>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportError {
+    Empty,
+    InconsistentWidth {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    NotBinary {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// Only reported by [`validate`], since repeated readings are still readings
+    Duplicate {
+        line: usize,
+        first: usize,
+    },
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::Empty => write!(f, "the report has no readings"),
+            ReportError::InconsistentWidth {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} bits like the first reading, found {}",
+                line, expected, found
+            ),
+            ReportError::NotBinary {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {}, column {}: {:?} isn't a bit",
+                line, column, character
+            ),
+            ReportError::Duplicate { line, first } => {
+                write!(f, "line {}: same reading as line {}", line, first)
+            }
+        }
+    }
+}
+
+/// Checks a whole report, finding every bad character, line of the wrong width and
+/// repeated reading rather than stopping at the first.
+pub fn validate(input: &str) -> Vec<ReportError> {
+    let mut errors = vec![];
+    let mut width = None;
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let found = line.chars().count();
+        let width = *width.get_or_insert(found);
+        if found != width {
+            errors.push(ReportError::InconsistentWidth {
+                line: line_number,
+                expected: width,
+                found,
+            });
+        }
+
+        let mut binary = true;
+        for (column, character) in line.chars().enumerate() {
+            if character != '0' && character != '1' {
+                binary = false;
+                errors.push(ReportError::NotBinary {
+                    line: line_number,
+                    column: column + 1,
+                    character,
+                });
+            }
+        }
+
+        if binary && found == width {
+            let first = *seen.entry(line).or_insert(line_number);
+            if first != line_number {
+                errors.push(ReportError::Duplicate {
+                    line: line_number,
+                    first,
+                });
+            }
+        }
+    }
+
+    if width.unwrap_or(0) == 0 {
+        errors.insert(0, ReportError::Empty);
+    }
+    errors
+}
+
+impl Report {
+    /// Reads one reading per line, taking the width from the first.
+    pub fn parse(input: &str) -> Result<Report, ReportError> {
+        let mut width = None;
+        let mut readings = vec![];
+        for (index, line) in input.lines().enumerate() {
+            let line_number = index + 1;
+            let found = line.chars().count();
+            let width = *width.get_or_insert(found);
+            if found != width {
+                return Err(ReportError::InconsistentWidth {
+                    line: line_number,
+                    expected: width,
+                    found,
+                });
+            }
+
+            let mut reading = BigUint::default();
+            for (column, character) in line.chars().enumerate() {
+                let bit = match character {
+                    '0' => 0,
+                    '1' => 1,
+                    _ => {
+                        return Err(ReportError::NotBinary {
+                            line: line_number,
+                            column: column + 1,
+                            character,
+                        })
+                    }
+                };
+                reading.push_bit(bit);
+            }
+            readings.push(reading);
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Report { width, readings }),
+            _ => Err(ReportError::Empty),
+        }
+    }
+
+    /// The bit holding `column`, counting columns from the left.
+    pub fn bit(&self, column: usize) -> usize {
+        self.width - 1 - column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_packs_readings() {
+        let report = Report::parse("10110\n00001\n").unwrap();
+        assert_eq!(report.width, 5);
+        assert_eq!(report.readings, vec![BigUint::from(22), BigUint::from(1)]);
+        assert_eq!(report.bit(0), 4);
+
+        let wide = Report::parse(&format!("1{}", "0".repeat(99))).unwrap();
+        assert_eq!(wide.width, 100);
+        assert_eq!(wide.readings[0].bit(wide.bit(0)), 1);
+        assert_eq!(wide.readings[0].bit(wide.bit(1)), 0);
+    }
+
+    #[test]
+    fn it_rejects_bad_reports() {
+        assert_eq!(Report::parse(""), Err(ReportError::Empty));
+        assert_eq!(
+            Report::parse("10110\n0001\n"),
+            Err(ReportError::InconsistentWidth {
+                line: 2,
+                expected: 5,
+                found: 4,
+            })
+        );
+        assert_eq!(
+            Report::parse("10110\n10210").unwrap_err().to_string(),
+            "line 2, column 3: '2' isn't a bit"
+        );
+    }
+
+    #[test]
+    fn it_finds_every_problem() {
+        let errors: Vec<String> = validate("101\n1x1\n0110\n101\n2a1\n101")
+            .iter()
+            .map(|error| error.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 2, column 2: 'x' isn't a bit",
+                "line 3: expected 3 bits like the first reading, found 4",
+                "line 4: same reading as line 1",
+                "line 5, column 1: '2' isn't a bit",
+                "line 5, column 2: 'a' isn't a bit",
+                "line 6: same reading as line 1",
+            ]
+        );
+        assert_eq!(validate("01\n10"), vec![]);
+        assert_eq!(validate("\n"), vec![ReportError::Empty]);
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use crate::report::Report;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Column {
+    pub ones: usize,
+    /// The fraction of readings with a 1 here
+    pub ratio: f64,
+    /// In bits, so 1 for an even split and 0 when every reading agrees
+    pub entropy: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub readings: usize,
+    pub columns: Vec<Column>,
+    /// The correlation between every pair of columns, or `None` if either never
+    /// changes
+    pub correlation: Vec<Vec<Option<f64>>>,
+}
+
+fn entropy(ratio: f64) -> f64 {
+    [ratio, 1.0 - ratio]
+        .iter()
+        .filter(|p| **p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+pub fn statistics(report: &Report) -> Statistics {
+    let n = report.readings.len();
+    let bits: Vec<Vec<bool>> = report
+        .readings
+        .iter()
+        .map(|reading| {
+            (0..report.width)
+                .map(|column| reading.bit(report.bit(column)) == 1)
+                .collect()
+        })
+        .collect();
+
+    let ones: Vec<usize> = (0..report.width)
+        .map(|column| bits.iter().filter(|reading| reading[column]).count())
+        .collect();
+    let columns = ones
+        .iter()
+        .map(|ones| {
+            let ratio = *ones as f64 / n as f64;
+            Column {
+                ones: *ones,
+                ratio,
+                entropy: entropy(ratio),
+            }
+        })
+        .collect();
+
+    // Pearson's correlation, which for two bits comes down to counting both-ones
+    let correlation = (0..report.width)
+        .map(|a| {
+            (0..report.width)
+                .map(|b| {
+                    let both = bits
+                        .iter()
+                        .filter(|reading| reading[a] && reading[b])
+                        .count();
+                    let spread =
+                        (ones[a] * (n - ones[a])) as f64 * (ones[b] * (n - ones[b])) as f64;
+                    let covariance = (both * n) as f64 - (ones[a] * ones[b]) as f64;
+                    (spread > 0.0).then(|| covariance / spread.sqrt())
+                })
+                .collect()
+        })
+        .collect();
+
+    Statistics {
+        readings: n,
+        columns,
+        correlation,
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} readings", self.readings)?;
+        writeln!(f, "column    ones  ratio  entropy")?;
+        for (index, column) in self.columns.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>6}  {:>6}  {:.3}    {:.3}",
+                index, column.ones, column.ratio, column.entropy
+            )?;
+        }
+
+        writeln!(f, "correlation")?;
+        write!(f, "{:>6}", "")?;
+        for index in 0..self.columns.len() {
+            write!(f, "  {:>6}", index)?;
+        }
+        writeln!(f)?;
+        for (index, row) in self.correlation.iter().enumerate() {
+            write!(f, "{:>6}", index)?;
+            for value in row {
+                match value {
+                    Some(value) => write!(f, "  {:>6.3}", value)?,
+                    None => write!(f, "  {:>6}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_measures_each_column() {
+        let report = Report::parse("110\n001\n111\n000").unwrap();
+        let stats = statistics(&report);
+        assert_eq!(stats.columns[2].ones, 2);
+        assert_eq!(stats.columns[2].ratio, 0.5);
+        assert_eq!(stats.columns[2].entropy, 1.0);
+        // The first two columns always match, and the third is independent of both
+        assert_eq!(stats.correlation[0][1], Some(1.0));
+        assert_eq!(stats.correlation[0][2], Some(0.0));
+
+        let report = Report::parse("10\n11\n10\n11").unwrap();
+        let stats = statistics(&report);
+        assert_eq!(stats.columns[0].entropy, 0.0);
+        assert_eq!(stats.correlation[0][1], None);
+
+        // Three ones to one zero
+        let report = Report::parse("1\n1\n1\n0").unwrap();
+        let entropy = statistics(&report).columns[0].entropy;
+        assert!((entropy - 0.811).abs() < 0.001);
+    }
+
+    #[test]
+    fn it_prints_a_table() {
+        let report = Report::parse("10\n01").unwrap();
+        assert_eq!(
+            statistics(&report).to_string(),
+            "2 readings
+column    ones  ratio  entropy
+     0       1  0.500    1.000
+     1       1  0.500    1.000
+correlation
+             0       1
+     0   1.000  -1.000
+     1  -1.000   1.000
+"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::criteria::Criteria;
+use crate::report::Report;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// Indexes into `Trie::nodes`, where 0 means no child since nothing points at the root
+    children: [usize; 2],
+    /// How many readings pass through this node
+    count: usize,
+    /// The index of the earliest of those readings in the report
+    first: usize,
+}
+
+/// Every reading of a report as a path of bits, taken in a fixed column order.
+/// Building it is O(n·w), and each rating is then a single walk from the root.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    depth: usize,
+}
+
+impl Trie {
+    pub fn new(report: &Report, columns: &[usize]) -> Trie {
+        let empty = Node {
+            children: [0; 2],
+            count: 0,
+            first: 0,
+        };
+        let mut nodes = vec![empty];
+        for (index, reading) in report.readings.iter().enumerate() {
+            let mut node = 0;
+            let mut columns = columns.iter();
+            loop {
+                let current = &mut nodes[node];
+                if current.count == 0 {
+                    current.first = index;
+                }
+                current.count += 1;
+                let Some(column) = columns.next() else {
+                    break;
+                };
+
+                let bit = reading.bit(report.bit(*column)) as usize;
+                if nodes[node].children[bit] == 0 {
+                    nodes[node].children[bit] = nodes.len();
+                    nodes.push(empty);
+                }
+                node = nodes[node].children[bit];
+            }
+        }
+
+        Trie {
+            nodes,
+            depth: columns.len(),
+        }
+    }
+
+    fn count(&self, child: usize) -> usize {
+        match child {
+            0 => 0,
+            child => self.nodes[child].count,
+        }
+    }
+
+    /// Follows `criteria` down the trie, in the trie's column order rather than the
+    /// criteria's. Gives the index of the same survivor as filtering the report.
+    pub fn walk(&self, criteria: &Criteria) -> Option<usize> {
+        let mut node = &self.nodes[0];
+        for _ in 0..self.depth {
+            if node.count <= 1 {
+                break;
+            }
+            let [zeroes, ones] = node.children.map(|child| self.count(child));
+            let kept = criteria.choose(zeroes, ones);
+            node = &self.nodes[node.children[kept as usize]];
+        }
+        (node.count > 0).then_some(node.first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big::BigUint;
+    use crate::criteria::Keep;
+    use crate::explain::explain;
+
+    const INPUT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
+
+    /// A report of `count` readings from a little linear congruential generator.
+    fn random_report(seed: u64, count: usize, width: usize) -> Report {
+        let mut state = seed;
+        let readings = (0..count)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                BigUint::from((state >> 11) & ((1 << width) - 1))
+            })
+            .collect();
+        Report { width, readings }
+    }
+
+    #[test]
+    fn it_walks_to_each_rating() {
+        let report = Report::parse(INPUT).unwrap();
+        let trie = Trie::new(&report, &[0, 1, 2, 3, 4]);
+        // 10111 and 01010
+        assert_eq!(trie.walk(&Criteria::OXYGEN_GENERATOR), Some(3));
+        assert_eq!(trie.walk(&Criteria::CO2_SCRUBBER), Some(11));
+
+        let empty = Report {
+            width: 5,
+            readings: vec![],
+        };
+        assert_eq!(Trie::new(&empty, &[0]).walk(&Criteria::CO2_SCRUBBER), None);
+    }
+
+    #[test]
+    fn it_agrees_with_filtering() {
+        let orders = [None, Some(vec![7, 6, 5, 4]), Some(vec![3])];
+        for seed in 0..50 {
+            // Narrow enough for plenty of ties and duplicates
+            let report = random_report(seed, 1 + seed as usize * 7, 8);
+            for order in &orders {
+                for keep in [Keep::MostCommon, Keep::LeastCommon] {
+                    for tie in [0, 1] {
+                        let criteria = Criteria {
+                            keep,
+                            tie,
+                            order: order.clone(),
+                        };
+                        let trie = Trie::new(&report, &criteria.columns(8));
+                        assert_eq!(
+                            trie.walk(&criteria).map(|index| &report.readings[index]),
+                            explain(&criteria, &report).rating.as_ref(),
+                            "seed {} with {:?}",
+                            seed,
+                            criteria
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::rule::{Pattern, WinRule};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    Empty,
+    Ragged {
+        expected: usize,
+        found: usize,
+    },
+    BadNumber(String),
+    Repeated(i64),
+    /// A rule that can't be played on the board
+    Rule(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::Empty => write!(f, "the board has no numbers"),
+            ErrorKind::Ragged { expected, found } => write!(
+                f,
+                "expected {} numbers like the first row, found {}",
+                expected, found
+            ),
+            ErrorKind::BadNumber(value) => write!(f, "couldn't parse number {:?}", value),
+            ErrorKind::Repeated(number) => write!(f, "{} is already on the board", number),
+            ErrorKind::Rule(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The 1-based line, counting from the start of whatever was parsed
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// A bingo card that remembers which of its cells have been marked, so each draw
+/// only touches the one cell it hits.
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub rows: usize,
+    pub columns: usize,
+    pub raw: Vec<i64>,
+    /// Which cell holds each number. Numbers don't repeat on a card.
+    index: HashMap<i64, usize>,
+    /// One bit per cell, in the same order as `raw`
+    marked: Vec<u64>,
+    /// The ways this card can win, none until rules are applied
+    patterns: Vec<Pattern>,
+    /// How many cells of each pattern are still unmarked
+    remaining: Vec<usize>,
+    /// The patterns each cell is part of
+    by_cell: Vec<Vec<usize>>,
+    unmarked_sum: i64,
+}
+
+impl Board {
+    /// Reads a card with one row per line, taking its size from the text. Every row
+    /// needs as many numbers as the first, and blank lines are skipped. The card can't
+    /// win until [`Board::apply`] gives it rules.
+    pub fn parse(block: &str) -> Result<Board, ParseError> {
+        let mut raw = vec![];
+        let mut index = HashMap::new();
+        let mut rows = 0;
+        let mut columns = None;
+        for (line, text) in block.lines().enumerate() {
+            let error = |kind| ParseError {
+                line: line + 1,
+                kind,
+            };
+            let values: Vec<&str> = text.split_whitespace().collect();
+            if values.is_empty() {
+                continue;
+            }
+            let expected = *columns.get_or_insert(values.len());
+            if values.len() != expected {
+                return Err(error(ErrorKind::Ragged {
+                    expected,
+                    found: values.len(),
+                }));
+            }
+
+            for value in values {
+                let number = value
+                    .parse::<i64>()
+                    .map_err(|_| error(ErrorKind::BadNumber(value.to_string())))?;
+                if index.insert(number, raw.len()).is_some() {
+                    return Err(error(ErrorKind::Repeated(number)));
+                }
+                raw.push(number);
+            }
+            rows += 1;
+        }
+
+        let Some(columns) = columns else {
+            return Err(ParseError {
+                line: 1,
+                kind: ErrorKind::Empty,
+            });
+        };
+        Ok(Board {
+            rows,
+            columns,
+            index,
+            marked: vec![0; raw.len().div_ceil(64)],
+            patterns: vec![],
+            remaining: vec![],
+            by_cell: vec![vec![]; raw.len()],
+            unmarked_sum: raw.iter().sum(),
+            raw,
+        })
+    }
+
+    /// Replaces the ways this card can win, counting any cells already marked. Rules
+    /// go on before play, since a pattern complete by then has nothing left to mark
+    /// and won't win. A rule that can't be played on a card this size is an error.
+    pub fn apply(&mut self, rules: &[Box<dyn WinRule>]) -> Result<(), ErrorKind> {
+        for rule in rules {
+            rule.check(self.rows, self.columns)
+                .map_err(ErrorKind::Rule)?;
+        }
+        self.patterns = rules
+            .iter()
+            .flat_map(|rule| rule.patterns(self.rows, self.columns))
+            .collect();
+        self.by_cell = vec![vec![]; self.raw.len()];
+        self.remaining = vec![];
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            for cell in &pattern.cells {
+                self.by_cell[*cell].push(index);
+            }
+            let unmarked = pattern
+                .cells
+                .iter()
+                .filter(|cell| !self.is_marked(**cell))
+                .count();
+            self.remaining.push(unmarked);
+        }
+        Ok(())
+    }
+
+    pub fn as_rows(&self) -> Vec<&[i64]> {
+        self.raw.chunks(self.columns).collect()
+    }
+
+    pub fn is_marked(&self, cell: usize) -> bool {
+        self.marked[cell / 64] >> (cell % 64) & 1 == 1
+    }
+
+    /// Marks `number` if it's on the card, returning the names of any patterns that
+    /// completed.
+    pub fn mark(&mut self, number: i64) -> Vec<String> {
+        let Some(&cell) = self.index.get(&number) else {
+            return vec![];
+        };
+        if self.is_marked(cell) {
+            return vec![];
+        }
+        self.marked[cell / 64] |= 1 << (cell % 64);
+        self.unmarked_sum -= number;
+
+        let mut completed = vec![];
+        for pattern in &self.by_cell[cell] {
+            self.remaining[*pattern] -= 1;
+            if self.remaining[*pattern] == 0 {
+                completed.push(self.patterns[*pattern].name.clone());
+            }
+        }
+        completed
+    }
+
+    /// The sum of every number that hasn't been marked yet.
+    pub fn unmarked_sum(&self) -> i64 {
+        self.unmarked_sum
+    }
+}
+
+/// One row per line, with a `*` after each marked number.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.as_rows() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|value| {
+                    let mark = if self.is_marked(self.index[value]) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    format!("{:>3}{}", value, mark)
+                })
+                .collect();
+            writeln!(f, "{}", cells.concat().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule;
+
+    /// A card that wins by the puzzle's rules.
+    fn board(block: &str) -> Board {
+        let mut board = Board::parse(block).unwrap();
+        board.apply(&rule::default_rules()).unwrap();
+        board
+    }
+
+    const BOARD: &str = r#"
+22 13 17 11  0
+8  2 23  4 24
+21  9 14 16  7
+6 10  3 18  5
+1 12 20 15 19
+"#;
+
+    const WINNER: &str = r#"
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7
+"#;
+
+    #[test]
+    fn it_creates_a_board() {
+        let board = Board::parse(BOARD).unwrap();
+        assert_eq!(board.rows, 5);
+        assert_eq!(board.columns, 5);
+        assert_eq!(board.raw.len(), 25);
+        assert_eq!(board.unmarked_sum(), 300);
+
+        // With no rules applied, even a full row doesn't win
+        let mut board = Board::parse("1 2\n3 4").unwrap();
+        assert!(board.mark(1).is_empty());
+        assert!(board.mark(2).is_empty());
+    }
+
+    #[test]
+    fn it_returns_a_board_as_rows() {
+        let board = Board::parse(BOARD).unwrap();
+        let rows = board.as_rows();
+        assert_eq!(rows[0], [22, 13, 17, 11, 0]);
+        assert_eq!(rows[1], [8, 2, 23, 4, 24]);
+        assert_eq!(rows[2], [21, 9, 14, 16, 7]);
+        assert_eq!(rows[3], [6, 10, 3, 18, 5]);
+        assert_eq!(rows[4], [1, 12, 20, 15, 19]);
+        assert_eq!(rows.len(), 5);
+    }
+
+    #[test]
+    fn it_reads_boards_of_any_size() {
+        let board = board("1 2 3\n4 5 6\n");
+        assert_eq!((board.rows, board.columns), (2, 3));
+        assert_eq!(board.as_rows(), vec![[1, 2, 3], [4, 5, 6]]);
+
+        // Filling the second column takes both rows, but a row takes all three columns
+        let mut board = board;
+        assert!(board.mark(2).is_empty());
+        assert_eq!(board.mark(5), vec!["column 1"]);
+        assert!(board.mark(4).is_empty());
+        assert_eq!(board.mark(6), vec!["row 1"]);
+    }
+
+    #[test]
+    fn it_rejects_bad_boards() {
+        let error = |block| Board::parse(block).unwrap_err().to_string();
+        assert_eq!(
+            error("\n1 2 3\n4 5\n"),
+            "line 3: expected 3 numbers like the first row, found 2"
+        );
+        assert_eq!(error("1 2\n3 x"), "line 2: couldn't parse number \"x\"");
+        assert_eq!(error("1 2\n3 1"), "line 2: 1 is already on the board");
+        assert_eq!(error(" \n"), "line 1: the board has no numbers");
+    }
+
+    #[test]
+    fn it_checks_if_the_board_is_a_winner() {
+        let mut board = board(WINNER);
+        let moves = [7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24];
+        let wins: Vec<Vec<String>> = moves.iter().map(|number| board.mark(*number)).collect();
+        // Only the last number, 24, completes the top row
+        assert_eq!(wins.iter().filter(|win| !win.is_empty()).count(), 1);
+        assert_eq!(wins[11], vec!["row 0"]);
+        assert!(board.is_marked(3));
+        assert!(!board.is_marked(5));
+        assert_eq!(
+            board.to_string(),
+            " 14* 21* 17* 24*  4*
+ 10  16  15   9* 19
+ 18   8  23* 26  20
+ 22  11* 13   6   5*
+  2*  0* 12   3   7*
+"
+        );
+    }
+
+    #[test]
+    fn it_wins_by_any_rule() {
+        let mut board = board(WINNER);
+        board
+            .apply(&[Box::new(rule::Diagonals), Box::new(rule::Corners)])
+            .unwrap();
+        // The anti-diagonal and the corners share 4 and 2
+        for number in [14, 4, 2, 9, 23] {
+            assert!(board.mark(number).is_empty());
+        }
+        assert_eq!(board.mark(11), vec!["anti-diagonal"]);
+        assert_eq!(board.mark(7), vec!["corners"]);
+
+        // Rules applied part way through count what's already marked, here 2 and 7
+        board
+            .apply(&[Box::new(rule::Rows), Box::new(rule::Columns)])
+            .unwrap();
+        assert!(board.mark(0).is_empty());
+        assert!(board.mark(12).is_empty());
+        assert_eq!(board.mark(3), vec!["row 4"]);
+    }
+
+    #[test]
+    fn it_counts_marks_made_before_the_rules() {
+        let mut board = Board::parse(WINNER).unwrap();
+        for number in [14, 10] {
+            assert!(board.mark(number).is_empty());
+        }
+        board.apply(&rule::default_rules()).unwrap();
+        // 14 and 10 were marked before the rules, and still count
+        assert!(board.mark(18).is_empty());
+        assert!(board.mark(22).is_empty());
+        assert_eq!(board.mark(2), vec!["column 0"]);
+    }
+
+    #[test]
+    fn it_rejects_rules_that_dont_fit() {
+        let mut board = Board::parse("1 2 3\n4 5 6").unwrap();
+        assert_eq!(
+            board.apply(&[Box::new(rule::Rows), Box::new(rule::Diagonals)]),
+            Err(ErrorKind::Rule(
+                "diagonals need a square board, not 2 by 3".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn it_gets_a_boards_score() {
+        let mut board = board(WINNER);
+        for number in [7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24] {
+            board.mark(number);
+        }
+        assert_eq!(board.unmarked_sum(), 188);
+
+        // Numbers that aren't on the card, or are already marked, change nothing
+        assert!(board.mark(99).is_empty());
+        assert!(board.mark(24).is_empty());
+        assert_eq!(board.unmarked_sum(), 188);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::board::Board;
+
+/// A board completing one of its patterns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Win {
+    /// The board's position in the input, from 0
+    pub board: usize,
+    /// How many numbers had been drawn, including the winning one
+    pub turn: usize,
+    pub last_number: i64,
+    /// The unmarked numbers' sum times the last number
+    pub score: i64,
+    /// Every pattern the last number completed
+    pub patterns: Vec<String>,
+}
+
+impl fmt::Display for Win {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "turn {}: board {} wins on {} by {} with a score of {}",
+            self.turn,
+            self.board,
+            self.last_number,
+            self.patterns.join(" and "),
+            self.score
+        )
+    }
+}
+
+/// Every board in play, drawn against one number at a time.
+#[derive(Debug, Clone)]
+pub struct Game {
+    boards: Vec<Board>,
+    won: Vec<bool>,
+    /// Which boards have each number, so a draw only visits those
+    holders: HashMap<i64, Vec<usize>>,
+    turn: usize,
+}
+
+impl Game {
+    pub fn new(boards: Vec<Board>) -> Game {
+        let mut holders: HashMap<i64, Vec<usize>> = HashMap::new();
+        for (index, board) in boards.iter().enumerate() {
+            for value in &board.raw {
+                holders.entry(*value).or_default().push(index);
+            }
+        }
+
+        Game {
+            won: vec![false; boards.len()],
+            boards,
+            holders,
+            turn: 0,
+        }
+    }
+
+    /// Marks `number` on every board that has it, returning the boards that won with
+    /// it in input order. Boards that have already won are out of the game.
+    pub fn draw(&mut self, number: i64) -> Vec<Win> {
+        self.turn += 1;
+        let mut wins = vec![];
+        for index in self.holders.get(&number).into_iter().flatten() {
+            let board = &mut self.boards[*index];
+            if self.won[*index] {
+                continue;
+            }
+            let patterns = board.mark(number);
+            if !patterns.is_empty() {
+                self.won[*index] = true;
+                wins.push(Win {
+                    board: *index,
+                    turn: self.turn,
+                    last_number: number,
+                    score: board.unmarked_sum() * number,
+                    patterns,
+                });
+            }
+        }
+        wins
+    }
+
+    pub fn board(&self, index: usize) -> &Board {
+        &self.boards[index]
+    }
+
+    /// Draws every number in turn, returning each win as it happens.
+    pub fn play(&mut self, numbers: &[i64]) -> Vec<Win> {
+        numbers
+            .iter()
+            .flat_map(|number| self.draw(*number))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule;
+
+    #[test]
+    fn it_reports_wins_as_they_happen() {
+        let boards = ["1 2\n3 4", "4 3\n9 8", "5 6\n7 1"]
+            .iter()
+            .map(|block| {
+                let mut board = Board::parse(block).unwrap();
+                board.apply(&rule::default_rules()).unwrap();
+                board
+            })
+            .collect();
+        let mut game = Game::new(boards);
+        assert_eq!(game.draw(4), vec![]);
+        // 3 completes a row on the first two boards at once
+        assert_eq!(
+            game.draw(3),
+            vec![
+                Win {
+                    board: 0,
+                    turn: 2,
+                    last_number: 3,
+                    score: 9,
+                    patterns: vec!["row 1".to_string()],
+                },
+                Win {
+                    board: 1,
+                    turn: 2,
+                    last_number: 3,
+                    score: 51,
+                    patterns: vec!["row 0".to_string()],
+                },
+            ]
+        );
+        // Board 0 has already won, so only board 2 can
+        let wins = game.play(&[1, 2, 7]);
+        assert_eq!(wins.len(), 1);
+        assert_eq!(
+            wins[0].to_string(),
+            "turn 5: board 2 wins on 7 by row 1 with a score of 77"
+        );
+    }
+}
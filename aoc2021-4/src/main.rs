@@ -1,97 +1,57 @@
+mod board;
+mod game;
+mod rule;
+
+use aoc_common::input::{read_input, split_input_by_blankline};
+use board::{Board, ParseError};
+use game::{Game, Win};
+use rule::{Mask, WinRule};
+use std::env;
 use std::fs;
-use std::vec;
-use uuid::Uuid;
+use std::process;
 
-fn main() {
-    let input: String = fs::read_to_string("input").expect("couldn't read the file");
-
-    let part_one = part_one(&input);
-    dbg!(part_one);
-
-    let part_two = part_two(&input);
-    dbg!(part_two);
-}
-
-#[derive(Debug, Clone)]
-struct Board {
-    id: Uuid,
-    rows: usize,
-    columns: usize,
-    raw: Vec<i64>,
-}
-
-impl Board {
-    fn new(rows: usize, columns: usize, raw: &str) -> Self {
-        Board {
-            id: Uuid::new_v4(),
-            rows,
-            columns,
-            raw: raw
-                .split_whitespace()
-                .map(|value| value.parse::<i64>().unwrap())
-                .collect(),
-        }
-    }
+const USAGE: &str = r#"usage:
+    aoc2021-4 [--rules RULE,RULE,...]
+    aoc2021-4 [--rules RULE,RULE,...] --wins
 
-    fn as_rows(&self) -> Vec<&[i64]> {
-        self.raw.chunks(self.rows).collect()
-    }
-
-    fn as_columns(&self) -> Vec<Vec<i64>> {
-        let mut output: Vec<Vec<i64>> = vec![];
-        // Create n number of column vectors
-        for _ in 0..self.columns {
-            output.push(vec![])
-        }
+rules: rows, columns, diagonals, corners, blackout, mask:PATH
+a mask is a grid of X and . that wins wherever it fits, and rows,columns is the default
+diagonals need every board to be square, and a mask needs every board to be at least its size"#;
 
-        // Populate each column vector
-        for (index, value) in self.raw.iter().enumerate() {
-            let col_index = index % self.columns;
-            output[col_index].push(*value);
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let input: String = read_input();
+
+    let (rules, args) = match args.as_slice() {
+        [flag, rules, rest @ ..] if flag == "--rules" => {
+            let rules = parse_rules(rules).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                usage();
+            });
+            (rules, rest)
         }
-
-        output
-    }
-
-    fn is_winner(&self, moves: &[i64]) -> bool {
-        // Check for horizontal wins
-        for row in self.as_rows() {
-            let row = row.to_vec();
-            let marked_cells: Vec<i64> = row
-                .into_iter()
-                .filter(|cell| moves.contains(cell))
-                .collect();
-
-            if marked_cells.len() == self.columns {
-                return true;
-            }
+        args => (rule::default_rules(), args),
+    };
+    let (mut game, moves) = new_game(&input, &rules).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+
+    match args {
+        [] => {}
+        [flag] if flag == "--wins" => {
+            print_wins(game, &moves);
+            return;
         }
-
-        // Check for vertical wins
-        for column in self.as_columns() {
-            let column = column.to_vec();
-            let marked_cells: Vec<i64> = column
-                .into_iter()
-                .filter(|cell| moves.contains(cell))
-                .collect();
-            if marked_cells.len() == self.rows {
-                return true;
-            }
-        }
-
-        false
+        _ => usage(),
     }
 
-    fn get_score(&self, moves: &[i64]) -> i64 {
-        self.raw.iter().filter(|value| !moves.contains(value)).sum()
-    }
-}
+    let wins = game.play(&moves);
+    let part_one = part_one(&wins);
+    dbg!(part_one);
 
-fn split_input_by_blankline(input: &str) -> Vec<String> {
-    input
-        .split("\n\n")
-        .map(|string| string.to_string())
-        .collect()
+    let part_two = part_two(&wins);
+    dbg!(part_two);
 }
 
 fn get_moves(input: &str) -> Vec<i64> {
@@ -101,64 +61,69 @@ fn get_moves(input: &str) -> Vec<i64> {
         .collect()
 }
 
-fn part_one(input: &str) -> Option<i64> {
+/// Reads `RULE,RULE,...`, where each rule is built in or `mask:PATH`.
+fn parse_rules(spec: &str) -> Result<Vec<Box<dyn WinRule>>, String> {
+    spec.split(',')
+        .map(|name| match name.strip_prefix("mask:") {
+            Some(path) => {
+                let grid =
+                    fs::read_to_string(path).map_err(|_| format!("couldn't read {}", path))?;
+                let mask: Box<dyn WinRule> = Box::new(Mask::parse(path, &grid)?);
+                Ok(mask)
+            }
+            None => rule::builtin(name).ok_or_else(|| format!("unknown rule {:?}", name)),
+        })
+        .collect()
+}
+
+/// Reads the moves and every board, whatever its size, for the given rules. Errors
+/// give the line in the whole input.
+fn new_game(input: &str, rules: &[Box<dyn WinRule>]) -> Result<(Game, Vec<i64>), ParseError> {
     let inputs = split_input_by_blankline(input);
     // Get the moves
     let moves = get_moves(&inputs[0]);
 
-    // Build the boards
+    // Build the boards, skipping any blank blocks at the end
     let mut boards: Vec<Board> = vec![];
+    let mut first_line = inputs[0].split('\n').count() + 2;
     for board_string in &inputs[1..] {
-        boards.push(Board::new(5, 5, board_string));
+        if !board_string.trim().is_empty() {
+            let mut board = Board::parse(board_string).map_err(|error| ParseError {
+                line: error.line + first_line - 1,
+                ..error
+            })?;
+            board.apply(rules).map_err(|kind| ParseError {
+                line: first_line,
+                kind,
+            })?;
+            boards.push(board);
+        }
+        first_line += board_string.split('\n').count() + 1;
     }
 
-    // Get the first board to win
-    for index in 0..moves.len() {
-        for board in &boards {
-            if board.is_winner(&moves[0..index]) {
-                let score = board.get_score(&moves[0..index]);
-                let last_number = &moves[index - 1];
-                return Some(score * last_number);
-            }
+    Ok((Game::new(boards), moves))
+}
+
+/// Prints each win with the winning board as it stood.
+fn print_wins(mut game: Game, moves: &[i64]) {
+    for number in moves.iter().copied() {
+        for win in game.draw(number) {
+            println!("{}\n{}", win, game.board(win.board));
         }
     }
-    None
 }
 
-fn part_two(input: &str) -> Option<i64> {
-    let inputs = split_input_by_blankline(input);
-    // Get the moves
-    let moves = get_moves(&inputs[0]);
-
-    // Build the boards
-    let mut boards: Vec<Board> = vec![];
-    for board_string in &inputs[1..] {
-        boards.push(Board::new(5, 5, board_string));
-    }
+fn part_one(wins: &[Win]) -> Option<i64> {
+    wins.first().map(|win| win.score)
+}
 
-    for index in 0..moves.len() {
-        let mut boards_to_remove: Vec<Uuid> = vec![];
-        // Check each board to see if they won this turn
-        for board in &boards {
-            if board.is_winner(&moves[0..index]) {
-                // If this is the last board, this is what we're looking for
-                if boards.len() == 1 {
-                    let score = board.get_score(&moves[0..index]);
-                    let last_number = &moves[index - 1];
-                    return Some(score * last_number);
-                }
-                // Otherwise, add this to the list of boards to remove
-                boards_to_remove.push(board.id);
-            }
-        }
+fn part_two(wins: &[Win]) -> Option<i64> {
+    wins.last().map(|win| win.score)
+}
 
-        // Update the list of boards, less any that should be removed
-        boards = boards
-            .into_iter()
-            .filter(|board| !boards_to_remove.contains(&board.id))
-            .collect();
-    }
-    None
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
 }
 
 #[cfg(test)]
@@ -198,97 +163,6 @@ mod test {
         assert_eq!(output.len(), 27);
     }
 
-    #[test]
-    fn it_creates_a_board() {
-        let input = r#"
-22 13 17 11  0
-8  2 23  4 24
-21  9 14 16  7
-6 10  3 18  5
-1 12 20 15 19
-"#
-        .to_string();
-        let board = Board::new(5, 5, &input);
-        assert_eq!(board.rows, 5);
-        assert_eq!(board.columns, 5);
-        assert_eq!(board.raw.len(), 25);
-    }
-
-    #[test]
-    fn it_returns_a_board_as_rows() {
-        let input = r#"
-22 13 17 11  0
-8  2 23  4 24
-21  9 14 16  7
-6 10  3 18  5
-1 12 20 15 19
-"#
-        .to_string();
-        let board = Board::new(5, 5, &input);
-        let rows = board.as_rows();
-        assert_eq!(rows[0], [22, 13, 17, 11, 0]);
-        assert_eq!(rows[1], [8, 2, 23, 4, 24]);
-        assert_eq!(rows[2], [21, 9, 14, 16, 7]);
-        assert_eq!(rows[3], [6, 10, 3, 18, 5]);
-        assert_eq!(rows[4], [1, 12, 20, 15, 19]);
-        assert_eq!(rows.len(), 5);
-    }
-
-    #[test]
-    fn it_returns_a_board_as_columns() {
-        let input = r#"
-22 13 17 11  0
-8  2 23  4 24
-21  9 14 16  7
-6 10  3 18  5
-1 12 20 15 19
-"#
-        .to_string();
-        let board = Board::new(5, 5, &input);
-        let columns = board.as_columns();
-        assert_eq!(columns[0], [22, 8, 21, 6, 1]);
-        assert_eq!(columns[1], [13, 2, 9, 10, 12]);
-        assert_eq!(columns[2], [17, 23, 14, 3, 20]);
-        assert_eq!(columns[3], [11, 4, 16, 18, 15]);
-        assert_eq!(columns[4], [0, 24, 7, 5, 19]);
-        assert_eq!(columns.len(), 5);
-    }
-
-    #[test]
-    fn it_checks_if_the_board_is_a_winner() {
-        let moves_string = "7,4,9,5,11,17,23,2,0,14,21,24".to_string();
-        let board_string = r#"
-14 21 17 24  4
-10 16 15  9 19
-18  8 23 26 20
-22 11 13  6  5
- 2  0 12  3  7
-"#
-        .to_string();
-
-        let moves = get_moves(&moves_string);
-        let board = Board::new(5, 5, &board_string);
-        assert_eq!(board.is_winner(&moves), true);
-    }
-
-    #[test]
-    fn it_gets_a_boards_score() {
-        let moves_string = "7,4,9,5,11,17,23,2,0,14,21,24".to_string();
-        let board_string = r#"
-14 21 17 24  4
-10 16 15  9 19
-18  8 23 26 20
-22 11 13  6  5
- 2  0 12  3  7
-"#
-        .to_string();
-
-        let moves = get_moves(&moves_string);
-        let board = Board::new(5, 5, &board_string);
-        assert_eq!(board.is_winner(&moves), true);
-        assert_eq!(board.get_score(&moves), 188);
-    }
-
     #[test]
     fn it_finds_the_first_winning_board() {
         let input = r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
@@ -311,7 +185,8 @@ mod test {
 22 11 13  6  5
 2  0 12  3  7"#
             .to_string();
-        let output = part_one(&input);
+        let (mut game, moves) = new_game(&input, &rule::default_rules()).unwrap();
+        let output = part_one(&game.play(&moves));
         assert_eq!(output, Some(4512));
     }
 
@@ -337,7 +212,71 @@ mod test {
 22 11 13  6  5
 2  0 12  3  7"#
             .to_string();
-        let output = part_two(&input);
+        let (mut game, moves) = new_game(&input, &rule::default_rules()).unwrap();
+        let output = part_two(&game.play(&moves));
         assert_eq!(output, Some(1924));
     }
+
+    #[test]
+    fn it_plays_boards_of_any_size() {
+        let input = "5,2,4,6,1\n\n1 2 3\n4 5 6\n\n2 7\n4 6\n8 9\n";
+        let (mut game, moves) = new_game(input, &rule::default_rules()).unwrap();
+        let wins = game.play(&moves);
+        // 5 then 2 fill the middle column of the two-row board, and 4 then 6 fill the
+        // middle row of the three-row one
+        assert_eq!(part_one(&wins), Some((1 + 3 + 4 + 6) * 2));
+        assert_eq!(part_two(&wins), Some((7 + 8 + 9) * 6));
+    }
+
+    #[test]
+    fn it_reports_the_line_of_a_ragged_board() {
+        let error = new_game("1,2\n\n1 2\n3 4\n\n5 6\n7", &rule::default_rules()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 7: expected 2 numbers like the first row, found 1"
+        );
+    }
+
+    #[test]
+    fn it_rejects_rules_a_board_cant_play() {
+        let input = "1,2\n\n1 2\n3 4\n\n1 2 3\n4 5 6";
+        let error = new_game(input, &parse_rules("rows,diagonals").unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 6: diagonals need a square board, not 2 by 3"
+        );
+    }
+
+    #[test]
+    fn it_rejects_masks_a_board_cant_fit() {
+        let input = "1,2\n\n1 2 3\n4 5 6\n\n1 2\n3 4";
+        let rules: Vec<Box<dyn WinRule>> = vec![Box::new(Mask::parse("wide", "XXX").unwrap())];
+        let error = new_game(input, &rules).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 6: mask wide is 1 by 3, bigger than a 2 by 2 board"
+        );
+    }
+
+    #[test]
+    fn it_parses_rules() {
+        let rules = parse_rules("diagonals,blackout").unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].patterns(2, 2)[0].name, "blackout");
+        assert_eq!(
+            parse_rules("rows,lines").unwrap_err(),
+            "unknown rule \"lines\""
+        );
+    }
+
+    #[test]
+    fn it_plays_by_other_rules() {
+        let input = "1,5,9,3,7\n\n1 2 3\n4 5 6\n7 8 9\n\n9 8 7\n6 5 4\n3 2 1";
+        let (mut game, moves) = new_game(input, &parse_rules("diagonals").unwrap()).unwrap();
+        let wins = game.play(&moves);
+        // 1, 5 and 9 are the first board's diagonal and the second's too, reversed
+        assert_eq!(wins.len(), 2);
+        assert_eq!(wins[0].patterns, vec!["diagonal"]);
+        assert_eq!(part_one(&wins), Some((2 + 3 + 4 + 6 + 7 + 8) * 9));
+    }
 }
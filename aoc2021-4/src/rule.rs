@@ -0,0 +1,282 @@
+use std::fmt;
+
+/// Cells that win together once every one of them is marked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub name: String,
+    /// Indexes in row-major order
+    pub cells: Vec<usize>,
+}
+
+/// A way to win, as the patterns it allows on a board of a given size.
+pub trait WinRule: fmt::Debug {
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern>;
+
+    /// Why the rule can't be played on a board of this size, if it can't.
+    fn check(&self, _rows: usize, _columns: usize) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rows;
+
+impl WinRule for Rows {
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        (0..rows)
+            .map(|row| Pattern {
+                name: format!("row {}", row),
+                cells: (0..columns).map(|column| row * columns + column).collect(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Columns;
+
+impl WinRule for Columns {
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        (0..columns)
+            .map(|column| Pattern {
+                name: format!("column {}", column),
+                cells: (0..rows).map(|row| row * columns + column).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Both corner-to-corner lines, which only square boards have. Playing them on any
+/// other board fails its [`WinRule::check`].
+#[derive(Debug, Clone, Copy)]
+pub struct Diagonals;
+
+impl WinRule for Diagonals {
+    fn check(&self, rows: usize, columns: usize) -> Result<(), String> {
+        if rows != columns {
+            return Err(format!(
+                "diagonals need a square board, not {} by {}",
+                rows, columns
+            ));
+        }
+        Ok(())
+    }
+
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        if rows != columns {
+            return vec![];
+        }
+        vec![
+            Pattern {
+                name: "diagonal".to_string(),
+                cells: (0..rows).map(|i| i * columns + i).collect(),
+            },
+            Pattern {
+                name: "anti-diagonal".to_string(),
+                cells: (0..rows).map(|i| i * columns + columns - 1 - i).collect(),
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Corners;
+
+impl WinRule for Corners {
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        let last = rows * columns - 1;
+        let mut cells = vec![0, columns - 1, last + 1 - columns, last];
+        cells.sort();
+        cells.dedup();
+        vec![Pattern {
+            name: "corners".to_string(),
+            cells,
+        }]
+    }
+}
+
+/// Every cell on the card.
+#[derive(Debug, Clone, Copy)]
+pub struct Blackout;
+
+impl WinRule for Blackout {
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        vec![Pattern {
+            name: "blackout".to_string(),
+            cells: (0..rows * columns).collect(),
+        }]
+    }
+}
+
+/// A shape drawn as a grid of `X` and `.`, which wins wherever it fits on the board.
+/// A board smaller than the grid fails its [`WinRule::check`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+    /// The `X`s as `(row, column)`
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Mask {
+    pub fn parse(name: &str, grid: &str) -> Result<Mask, String> {
+        let lines: Vec<&str> = grid
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let columns = lines.first().map_or(0, |line| line.chars().count());
+        let mut cells = vec![];
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(format!(
+                    "row {} of mask {} isn't {} wide like the first",
+                    row + 1,
+                    name,
+                    columns
+                ));
+            }
+            for (column, character) in line.chars().enumerate() {
+                match character {
+                    'X' => cells.push((row, column)),
+                    '.' => {}
+                    _ => {
+                        return Err(format!(
+                            "mask {} can only use X and ., found {:?}",
+                            name, character
+                        ))
+                    }
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Err(format!("mask {} has no X in it", name));
+        }
+
+        Ok(Mask {
+            name: name.to_string(),
+            rows: lines.len(),
+            columns,
+            cells,
+        })
+    }
+}
+
+impl WinRule for Mask {
+    fn check(&self, rows: usize, columns: usize) -> Result<(), String> {
+        if self.rows > rows || self.columns > columns {
+            return Err(format!(
+                "mask {} is {} by {}, bigger than a {} by {} board",
+                self.name, self.rows, self.columns, rows, columns
+            ));
+        }
+        Ok(())
+    }
+
+    fn patterns(&self, rows: usize, columns: usize) -> Vec<Pattern> {
+        if self.rows > rows || self.columns > columns {
+            return vec![];
+        }
+        let mut patterns = vec![];
+        for top in 0..=rows - self.rows {
+            for left in 0..=columns - self.columns {
+                patterns.push(Pattern {
+                    name: format!("{} at row {}, column {}", self.name, top, left),
+                    cells: self
+                        .cells
+                        .iter()
+                        .map(|(row, column)| (top + row) * columns + left + column)
+                        .collect(),
+                });
+            }
+        }
+        patterns
+    }
+}
+
+/// The rules from the puzzle.
+pub fn default_rules() -> Vec<Box<dyn WinRule>> {
+    vec![Box::new(Rows), Box::new(Columns)]
+}
+
+/// Looks up a built-in rule by name.
+pub fn builtin(name: &str) -> Option<Box<dyn WinRule>> {
+    let rule: Box<dyn WinRule> = match name {
+        "rows" => Box::new(Rows),
+        "columns" => Box::new(Columns),
+        "diagonals" => Box::new(Diagonals),
+        "corners" => Box::new(Corners),
+        "blackout" => Box::new(Blackout),
+        _ => return None,
+    };
+    Some(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(rule: &dyn WinRule, rows: usize, columns: usize) -> Vec<Vec<usize>> {
+        rule.patterns(rows, columns)
+            .into_iter()
+            .map(|pattern| pattern.cells)
+            .collect()
+    }
+
+    #[test]
+    fn it_builds_lines() {
+        assert_eq!(cells(&Rows, 2, 3), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(
+            cells(&Columns, 2, 3),
+            vec![vec![0, 3], vec![1, 4], vec![2, 5]]
+        );
+        assert_eq!(cells(&Diagonals, 3, 3), vec![vec![0, 4, 8], vec![2, 4, 6]]);
+        assert!(cells(&Diagonals, 2, 3).is_empty());
+        assert!(Diagonals.check(2, 3).is_err());
+        assert_eq!(Diagonals.check(3, 3), Ok(()));
+        assert_eq!(cells(&Corners, 2, 3), vec![vec![0, 2, 3, 5]]);
+        assert_eq!(cells(&Corners, 1, 1), vec![vec![0]]);
+        assert_eq!(cells(&Blackout, 2, 2), vec![vec![0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn it_places_masks_wherever_they_fit() {
+        let mask = Mask::parse("step", "X.\n.X\n").unwrap();
+        assert_eq!(mask.cells, vec![(0, 0), (1, 1)]);
+        // On a 2x3 board it fits with its top left corner in column 0 or 1
+        let patterns = mask.patterns(2, 3);
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[1].name, "step at row 0, column 1");
+        assert_eq!(patterns[1].cells, vec![1, 5]);
+        assert!(mask.patterns(1, 5).is_empty());
+    }
+
+    #[test]
+    fn it_rejects_masks_bigger_than_the_board() {
+        let mask = Mask::parse("step", "X.\n.X\n").unwrap();
+        assert_eq!(mask.check(2, 3), Ok(()));
+        assert_eq!(mask.check(2, 2), Ok(()));
+        assert_eq!(
+            mask.check(1, 5),
+            Err("mask step is 2 by 2, bigger than a 1 by 5 board".to_string())
+        );
+        assert!(mask.check(3, 1).is_err());
+    }
+
+    #[test]
+    fn it_rejects_bad_masks() {
+        assert_eq!(
+            Mask::parse("m", "X.\nX"),
+            Err("row 2 of mask m isn't 2 wide like the first".to_string())
+        );
+        assert_eq!(
+            Mask::parse("m", "Xo"),
+            Err("mask m can only use X and ., found 'o'".to_string())
+        );
+        assert_eq!(
+            Mask::parse("m", "..\n"),
+            Err("mask m has no X in it".to_string())
+        );
+    }
+}
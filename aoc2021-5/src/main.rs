@@ -1,9 +1,8 @@
+use aoc_common::input::read_input;
 use std::collections::HashMap;
-use std::fs;
 
 fn main() {
-    let input: Vec<String> = fs::read_to_string("input")
-        .expect("couldn't read the file")
+    let input: Vec<String> = read_input()
         .lines()
         .map(|line| line.parse().expect("couldn't parse line"))
         .collect();
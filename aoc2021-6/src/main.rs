@@ -1,21 +1,34 @@
+use aoc_common::checked::{self, Location, Overflow};
+use aoc_common::input::read_input;
 use std::collections::HashMap;
-use std::fs;
+
+// The initial state is a single line of timers
+const SCHOOL: Location = Location::line(6, 1);
 
 fn main() {
-    let initial_state: Vec<u8> = fs::read_to_string("input")
-        .expect("couldn't read the input file")
-        .split(',')
-        .map(|value| value.parse().unwrap())
-        .collect();
+    let initial_state = parse_timers(&read_input()).expect("couldn't read the timers");
 
-    let lake = fish_simulator(&initial_state, 80);
-    dbg!(lake.into_values().sum::<u64>());
+    let lake = fish_simulator(&initial_state, 80).expect("couldn't simulate 80 days");
+    dbg!(count_fish(lake).expect("couldn't count the fish"));
+
+    let lake = fish_simulator(&initial_state, 256).expect("couldn't simulate 256 days");
+    dbg!(count_fish(lake).expect("couldn't count the fish"));
+}
+
+fn parse_timers(input: &str) -> Result<Vec<u8>, checked::Error> {
+    input
+        .trim()
+        .split(',')
+        .map(|value| SCHOOL.parse(value, 10))
+        .collect()
+}
 
-    let lake = fish_simulator(&initial_state, 256);
-    dbg!(lake.into_values().sum::<u64>());
+fn count_fish(lake: HashMap<u8, u64>) -> Result<u64, Overflow> {
+    lake.into_values()
+        .try_fold(0, |total, count| SCHOOL.add(total, count))
 }
 
-fn fish_simulator(input: &[u8], duration: u32) -> HashMap<u8, u64> {
+fn fish_simulator(input: &[u8], duration: u32) -> Result<HashMap<u8, u64>, Overflow> {
     // Create the empty lake
     let mut lake: HashMap<u8, u64> = HashMap::new();
     for count in 0..9 {
@@ -25,7 +38,7 @@ fn fish_simulator(input: &[u8], duration: u32) -> HashMap<u8, u64> {
     // Update lake with initial state
     for fish in input {
         if let Some(count) = lake.get_mut(fish) {
-            *count += 1;
+            *count = SCHOOL.add(*count, 1)?;
         }
     }
 
@@ -50,12 +63,12 @@ fn fish_simulator(input: &[u8], duration: u32) -> HashMap<u8, u64> {
                 } else {
                     // New mom is in the delivery room
                     if let Some(new_moms) = next_lake.get_mut(&6) {
-                        *new_moms += count;
+                        *new_moms = SCHOOL.add(*new_moms, count)?;
                     }
 
                     // A new fish is born!
                     if let Some(newborns) = next_lake.get_mut(&8) {
-                        *newborns += count;
+                        *newborns = SCHOOL.add(*newborns, count)?;
                     }
                 }
             }
@@ -65,7 +78,7 @@ fn fish_simulator(input: &[u8], duration: u32) -> HashMap<u8, u64> {
         lake = next_lake;
     }
 
-    lake
+    Ok(lake)
 }
 
 #[cfg(test)]
@@ -79,7 +92,7 @@ mod tests {
             .map(|value| value.parse().unwrap())
             .collect();
 
-        let end_state: HashMap<u8, u64> = fish_simulator(&initial_state, 0);
+        let end_state: HashMap<u8, u64> = fish_simulator(&initial_state, 0).unwrap();
         assert_eq!(end_state.get(&1), Some(&1));
         assert_eq!(end_state.get(&2), Some(&1));
         assert_eq!(end_state.get(&3), Some(&2));
@@ -94,7 +107,7 @@ mod tests {
             .map(|value| value.parse().unwrap())
             .collect();
 
-        let end_state = fish_simulator(&initial_state, 2);
+        let end_state = fish_simulator(&initial_state, 2).unwrap();
         assert_eq!(end_state.get(&0), Some(&1));
         assert_eq!(end_state.get(&1), Some(&2));
         assert_eq!(end_state.get(&2), Some(&1));
@@ -110,8 +123,8 @@ mod tests {
             .map(|value| value.parse().unwrap())
             .collect();
 
-        let end_state = fish_simulator(&initial_state, 18);
-        assert_eq!(end_state.into_values().sum::<u64>(), 26);
+        let end_state = fish_simulator(&initial_state, 18).unwrap();
+        assert_eq!(count_fish(end_state), Ok(26));
     }
 
     #[test]
@@ -122,7 +135,34 @@ mod tests {
             .map(|value| value.parse().unwrap())
             .collect();
 
-        let end_state = fish_simulator(&initial_state, 256);
-        assert_eq!(end_state.into_values().sum::<u64>(), 26984457539);
+        let end_state = fish_simulator(&initial_state, 256).unwrap();
+        assert_eq!(count_fish(end_state), Ok(26984457539));
+    }
+
+    #[test]
+    fn it_reports_timers_that_arent_numbers() {
+        assert_eq!(parse_timers("3,4,3,1,2\n"), Ok(vec![3, 4, 3, 1, 2]));
+        assert_eq!(
+            parse_timers("3,4,x,1,2"),
+            Err(checked::Error::Invalid(checked::Invalid {
+                day: 6,
+                text: "x".to_string(),
+                line: Some(1),
+            }))
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_an_overflowing_school() {
+        use aoc_common::checked::Operation;
+        assert_eq!(
+            fish_simulator(&[3, 4, 3, 1, 2], 1000).unwrap_err(),
+            Overflow {
+                day: 6,
+                operation: Operation::Add,
+                line: Some(1),
+            }
+        );
     }
 }
@@ -1,46 +1,50 @@
+use aoc_common::checked::{self, Location, Overflow};
+use aoc_common::input::read_input;
 use std::collections::HashMap;
-use std::fs;
+
+// The crab positions are a single line
+const CRABS: Location = Location::line(7, 1);
 
 fn main() {
-    let input: Vec<i32> = fs::read_to_string("input")
-        .expect("couldn't read the input file")
-        .split(',')
-        .map(|value| {
-            value
-                .parse()
-                .expect("couldn't convert a string into a number")
-        })
-        .collect();
-
-    let cost = get_cheapest_destination_cost(&input);
+    let input = parse_positions(&read_input()).expect("couldn't read the crab positions");
+
+    let cost = get_cheapest_destination_cost(&input).expect("couldn't cost the destinations");
     dbg!(cost);
 
-    let cost = get_cheapest_destination_cost_v2(&input);
+    let cost = get_cheapest_destination_cost_v2(&input).expect("couldn't cost the destinations");
     dbg!(cost);
 }
 
-fn get_cheapest_destination_cost(input: &[i32]) -> Option<i32> {
+fn parse_positions(input: &str) -> Result<Vec<i32>, checked::Error> {
+    input
+        .trim()
+        .split(',')
+        .map(|value| CRABS.parse(value, 10))
+        .collect()
+}
+
+fn get_cheapest_destination_cost(input: &[i32]) -> Result<Option<i32>, Overflow> {
     // Calcuate the fuel cost for each destination
     let mut fuel_costs: HashMap<i32, i32> = HashMap::new();
     for destination in input {
         if fuel_costs.get(destination).is_none() {
-            let cost = calculate_destination_fuel_cost(input, *destination);
+            let cost = calculate_destination_fuel_cost(input, *destination)?;
             fuel_costs.insert(cost, *destination);
         }
     }
 
     // Return the cost of the cheapest destination
-    fuel_costs.into_keys().min()
+    Ok(fuel_costs.into_keys().min())
 }
 
-fn calculate_destination_fuel_cost(input: &[i32], destination: i32) -> i32 {
-    input
-        .iter()
-        .map(|&value| i32::abs(value - destination))
-        .sum::<i32>()
+fn calculate_destination_fuel_cost(input: &[i32], destination: i32) -> Result<i32, Overflow> {
+    input.iter().try_fold(0, |total, &value| {
+        let steps = i32::abs(CRABS.sub(value, destination)?);
+        CRABS.add(total, steps)
+    })
 }
 
-fn get_cheapest_destination_cost_v2(input: &[i32]) -> Option<i32> {
+fn get_cheapest_destination_cost_v2(input: &[i32]) -> Result<Option<i32>, Overflow> {
     let min = input.iter().min().expect("Couldn't find the minimum");
     let max = input.iter().max().expect("Couldn't find the maximum");
 
@@ -50,24 +54,21 @@ fn get_cheapest_destination_cost_v2(input: &[i32]) -> Option<i32> {
         // How expensive is it to move everyone there?
         if fuel_costs.get(&destination).is_none() {
             fuel_costs.insert(
-                calculate_destination_fuel_cost_v2(input, destination),
+                calculate_destination_fuel_cost_v2(input, destination)?,
                 destination,
             );
         }
     }
 
     // Return the cost of the cheapest destination
-    fuel_costs.clone().into_keys().min()
+    Ok(fuel_costs.clone().into_keys().min())
 }
 
-fn calculate_destination_fuel_cost_v2(input: &[i32], destination: i32) -> i32 {
-    input
-        .iter()
-        .map(|&value| {
-            let steps = i32::abs(value - destination);
-            steps * (steps + 1) / 2
-        })
-        .sum::<i32>()
+fn calculate_destination_fuel_cost_v2(input: &[i32], destination: i32) -> Result<i32, Overflow> {
+    input.iter().try_fold(0, |total, &value| {
+        let steps = i32::abs(CRABS.sub(value, destination)?);
+        CRABS.add(total, CRABS.mul(steps, CRABS.add(steps, 1)?)? / 2)
+    })
 }
 
 #[cfg(test)]
@@ -81,7 +82,7 @@ mod tests {
             .collect();
 
         let output = calculate_destination_fuel_cost(&input, 2);
-        assert_eq!(output, 37);
+        assert_eq!(output, Ok(37));
     }
 
     #[test]
@@ -92,7 +93,7 @@ mod tests {
             .collect();
 
         let output = get_cheapest_destination_cost(&input);
-        assert_eq!(output, Some(37));
+        assert_eq!(output, Ok(Some(37)));
     }
 
     #[test]
@@ -103,9 +104,9 @@ mod tests {
             .collect();
 
         let output = calculate_destination_fuel_cost_v2(&input, 5);
-        assert_eq!(output, 168);
+        assert_eq!(output, Ok(168));
         let output = calculate_destination_fuel_cost_v2(&input, 2);
-        assert_eq!(output, 206);
+        assert_eq!(output, Ok(206));
     }
 
     #[test]
@@ -116,6 +117,33 @@ mod tests {
             .collect();
 
         let output = get_cheapest_destination_cost_v2(&input);
-        assert_eq!(output, Some(168));
+        assert_eq!(output, Ok(Some(168)));
+    }
+
+    #[test]
+    fn it_reports_positions_that_arent_numbers() {
+        assert_eq!(parse_positions("16,1,2\n"), Ok(vec![16, 1, 2]));
+        assert_eq!(
+            parse_positions("16,one,2"),
+            Err(checked::Error::Invalid(checked::Invalid {
+                day: 7,
+                text: "one".to_string(),
+                line: Some(1),
+            }))
+        );
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn it_reports_overflowing_fuel_costs() {
+        use aoc_common::checked::Operation;
+        assert_eq!(
+            calculate_destination_fuel_cost_v2(&[0, 70000], 0),
+            Err(Overflow {
+                day: 7,
+                operation: Operation::Mul,
+                line: Some(1),
+            })
+        );
     }
 }